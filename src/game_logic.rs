@@ -1,32 +1,368 @@
-use std::{collections::HashMap, f32::consts::PI};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    f32::consts::PI,
+    sync::atomic::{self, AtomicU64},
+};
 
 use bevy::{
+    asset::LoadState,
     ecs::{schedule::common_conditions::any_with_component, system::SystemId},
     prelude::*,
     text::{Font, TextFont},
     ui::{Interaction, Node, PositionType, Val},
 };
+use serde::{Deserialize, Serialize};
 use shuftlib::{
     core::{Suit, italian::ItalianRank},
-    tressette::{Game, MoveEffect, TressetteCard},
-    trick_taking::{PLAYERS, PlayerId},
+    tressette::TressetteCard,
+    trick_taking::PlayerId,
+};
+use strum::EnumIter;
+
+use crate::{
+    camera::CANVAS_SIZE,
+    games::{
+        CardGame, MoveEffect, PendingPhase, RulesConfig,
+        briscola::Briscola,
+        deal::Deal,
+        marafone::Marafone,
+        record::{InvalidMove, Move},
+        rovescino::Rovescino,
+        scopa::Scopa,
+        terziglio::Terziglio,
+        tressette_due::TressetteDue,
+        tressette_quattro::TressetteQuattro,
+    },
 };
-use strum::IntoEnumIterator;
 
-use crate::camera::CANVAS_SIZE;
+mod accusi;
+mod auction;
+mod daily;
+mod deck;
+mod menu;
+mod notation;
+mod panel;
+mod pause;
+mod pending;
+mod plates;
+mod replay;
+mod rules;
+mod save;
+mod scenario;
+mod stats;
+mod summary;
+mod table;
+mod tricks;
+mod trump;
+mod undo;
+
+/// The game variant being played at the table, as chosen in the start menu.
+#[derive(
+    Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter, Serialize, Deserialize,
+)]
+pub enum Variant {
+    /// Four players in two teams.
+    #[default]
+    Tressette,
+    /// Two players drawing from a stock after each trick.
+    TressetteDue,
+    /// Three players, one of them calling to play alone.
+    Terziglio,
+    /// Three players, one of them playing a face up dummy hand.
+    Morto,
+    /// Two players with a trump suit, drawing after each trick.
+    Briscola,
+    /// Briscola for four players in two teams.
+    BriscolaQuattro,
+    /// Two players capturing cards from the table.
+    Scopa,
+    /// Scopa for four players in two teams.
+    ScopaQuattro,
+    /// Four players in two teams, all the cards dealt.
+    Scopone,
+    /// Scopone with the napola.
+    ScoponeNapola,
+    /// Four players in two teams, trump chosen by a player each hand.
+    Marafone,
+    /// Four players on their own, taking as few points as they can.
+    Rovescino,
+}
 
-#[derive(Resource)]
-struct GameState(Game);
+impl Variant {
+    /// Name shown in the start menu.
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Tressette => "Tressette",
+            Variant::TressetteDue => "Tressette a due",
+            Variant::Terziglio => "Terziglio",
+            Variant::Morto => "Tressette con il morto",
+            Variant::Briscola => "Briscola",
+            Variant::BriscolaQuattro => "Briscola a quattro",
+            Variant::Scopa => "Scopa",
+            Variant::ScopaQuattro => "Scopa a quattro",
+            Variant::Scopone => "Scopone Scientifico",
+            Variant::ScoponeNapola => "Scopone con napola",
+            Variant::Marafone => "Marafone",
+            Variant::Rovescino => "Tressette a perdere",
+        }
+    }
+
+    /// Whether the fewest points win, rather than the most.
+    pub fn fewest_points_win(self) -> bool {
+        matches!(self, Variant::Rovescino)
+    }
+
+    /// Whether the variant is played to the house rules chosen before the
+    /// game starts.
+    pub fn has_house_rules(self) -> bool {
+        matches!(self, Variant::Tressette | Variant::Morto)
+    }
+
+    /// Deals a new game of this variant, with the given house rules. Games
+    /// dealt from the same `seed` get the same cards.
+    pub fn new_game(self, rules: RulesConfig, seed: u64) -> Box<dyn CardGame> {
+        match self {
+            Variant::Tressette => Box::new(TressetteQuattro::new(rules, seed)),
+            Variant::TressetteDue => Box::new(TressetteDue::new(seed)),
+            Variant::Terziglio => Box::new(Terziglio::new(seed)),
+            Variant::Morto => Box::new(TressetteQuattro::morto(rules, seed)),
+            Variant::Briscola => Box::new(Briscola::new(2, seed)),
+            Variant::BriscolaQuattro => Box::new(Briscola::new(4, seed)),
+            Variant::Scopa => Box::new(Scopa::new(2, seed)),
+            Variant::ScopaQuattro => Box::new(Scopa::new(4, seed)),
+            Variant::Scopone => Box::new(Scopa::scopone(false, seed)),
+            Variant::ScoponeNapola => Box::new(Scopa::scopone(true, seed)),
+            Variant::Marafone => Box::new(Marafone::new(seed)),
+            Variant::Rovescino => Box::new(Rovescino::new(seed)),
+        }
+    }
+
+    /// Whether a game of this variant can start from a [`Deal`] of the whole
+    /// deck to four players.
+    pub fn takes_deals(self) -> bool {
+        matches!(
+            self,
+            Variant::Tressette | Variant::Morto | Variant::Rovescino
+        )
+    }
+
+    /// Deals a new game of this variant, its first hand dealt as in `deal`
+    /// when there's one, the hands after it shuffled from `seed`.
+    pub fn deal_game(
+        self,
+        rules: RulesConfig,
+        seed: u64,
+        deal: Option<&Deal>,
+    ) -> Box<dyn CardGame> {
+        match (self, deal) {
+            (Variant::Tressette, Some(deal)) => {
+                Box::new(TressetteQuattro::from_deal(rules, deal, seed))
+            }
+            (Variant::Morto, Some(deal)) => {
+                Box::new(TressetteQuattro::morto_from_deal(rules, deal, seed))
+            }
+            (Variant::Rovescino, Some(deal)) => Box::new(Rovescino::from_deal(deal, seed)),
+            _ => self.new_game(rules, seed),
+        }
+    }
+}
+
+/// Source of the ids telling games apart.
+static NEXT_GAME_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The game being played at the table, along with what it takes to deal it
+/// again and replay it: its variant, house rules, seed, the deal it started
+/// from if it wasn't shuffled and the moves made.
+#[derive(Resource, Deref, DerefMut)]
+struct GameState {
+    #[deref]
+    game: Box<dyn CardGame>,
+    /// Tells the game apart from every other one dealt since the app
+    /// started, even from the same seed. Taking moves back keeps it.
+    id: u64,
+    variant: Variant,
+    rules: RulesConfig,
+    seed: u64,
+    deal: Option<Deal>,
+    /// Date of the daily deal, when the game is one.
+    daily: Option<String>,
+    moves: Vec<Move>,
+}
+
+impl FromWorld for GameState {
+    fn from_world(world: &mut World) -> Self {
+        let variant = world.get_resource::<Variant>().copied().unwrap_or_default();
+        let rules = world
+            .get_resource::<RulesConfig>()
+            .copied()
+            .unwrap_or_default();
+        GameState::new(variant, rules)
+    }
+}
+
+impl GameState {
+    fn new(variant: Variant, rules: RulesConfig) -> Self {
+        Self::with_seed(variant, rules, rand::random())
+    }
+
+    fn with_seed(variant: Variant, rules: RulesConfig, seed: u64) -> Self {
+        Self::with_deal(variant, rules, seed, None)
+    }
+
+    fn with_deal(variant: Variant, rules: RulesConfig, seed: u64, deal: Option<Deal>) -> Self {
+        GameState {
+            game: variant.deal_game(rules, seed, deal.as_ref()),
+            id: NEXT_GAME_ID.fetch_add(1, atomic::Ordering::Relaxed),
+            variant,
+            rules,
+            seed,
+            deal,
+            daily: None,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Deals the same cards again, from the scenario or the seed the game was
+    /// dealt from, for it to be played from the start.
+    fn restart(&self) -> Self {
+        Self::with_deal(self.variant, self.rules, self.seed, self.deal.clone())
+    }
+
+    /// Deals the saved game again and replays its moves.
+    fn resume(saved: &save::SavedGame) -> Result<Self, InvalidMove> {
+        let mut game = GameState {
+            daily: saved.daily.clone(),
+            ..Self::with_deal(saved.variant, saved.rules, saved.seed, saved.deal.clone())
+        };
+        for mv in &saved.moves {
+            game.replay(mv.clone())?;
+        }
+        Ok(game)
+    }
+
+    /// Makes a move taken from a record of the game.
+    fn replay(&mut self, mv: Move) -> Result<(), InvalidMove> {
+        self.game.apply(&mv)?;
+        self.moves.push(mv);
+        Ok(())
+    }
+
+    /// Takes back the last move of the POV player, along with every move the
+    /// bots made after it, by dealing the game again and replaying the moves
+    /// that came before. Returns whether there was a move to take back.
+    fn undo(&mut self) -> bool {
+        let mut game = self
+            .variant
+            .deal_game(self.rules, self.seed, self.deal.as_ref());
+        let mut last_pov_move = None;
+        for (index, mv) in self.moves.iter().enumerate() {
+            if game.controller(game.current_player()) == PlayerId::PLAYER_0 {
+                last_pov_move = Some(index);
+            }
+            if let Err(e) = game.apply(mv) {
+                warn!("Invalid recorded move: {:?}", e);
+                return false;
+            }
+        }
+        let Some(index) = last_pov_move else {
+            return false;
+        };
+
+        let mut game = GameState {
+            id: self.id,
+            daily: self.daily.clone(),
+            ..Self::with_deal(self.variant, self.rules, self.seed, self.deal.clone())
+        };
+        for mv in &self.moves[..index] {
+            if game.replay(mv.clone()).is_err() {
+                return false;
+            }
+        }
+        *self = game;
+        true
+    }
+
+    /// Keeps track of a move just made, for the game to be saved.
+    fn record(&mut self, mv: Move) {
+        self.moves.push(mv);
+    }
+}
+
+/// Formats the score line for the score display. With more than two sides
+/// each one is a player, so the points are labelled with who made them.
+fn score_text(prefix: &str, score: &[u8]) -> String {
+    if score.len() > 2 {
+        let score: Vec<String> = score
+            .iter()
+            .enumerate()
+            .map(|(player, points)| {
+                let player = PlayerId::try_from(player).unwrap();
+                format!("{} {points}", player_label(player))
+            })
+            .collect();
+        return format!("{prefix}: {}", score.join(", "));
+    }
+    let score: Vec<String> = score.iter().map(u8::to_string).collect();
+    format!("{prefix}: {}", score.join(" - "))
+}
+
+/// Name of the suit in an Italian deck.
+fn suit_name(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Hearts => "Cups",
+        Suit::Diamonds => "Coins",
+        Suit::Clubs => "Clubs",
+        Suit::Spades => "Swords",
+    }
+}
+
+/// Name of a card as shown to players, e.g. "Knight of Swords".
+fn card_name(card: TressetteCard) -> String {
+    let rank = match card.rank() {
+        ItalianRank::Ace => "Ace".to_string(),
+        ItalianRank::Jack => "Jack".to_string(),
+        ItalianRank::Knight => "Knight".to_string(),
+        ItalianRank::King => "King".to_string(),
+        rank => (rank as u8).to_string(),
+    };
+    format!("{rank} of {}", suit_name(card.suit()))
+}
 
 #[derive(Resource, Default)]
 struct FontHandle(Handle<Font>);
 
-/// Positions for played cards in the trick (center of table, clockwise diamond)
+/// Side of the table a seat is drawn on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TableSide {
+    Bottom,
+    Right,
+    Top,
+    Left,
+}
+
+/// Returns the side of the table for `player_id`, given how many seats there
+/// are. The POV player always sits at the bottom.
+fn table_side(seats: usize, player_id: usize) -> TableSide {
+    match (seats, player_id) {
+        (_, 0) => TableSide::Bottom,
+        (2, 1) => TableSide::Top,
+        (3, 1) => TableSide::Right,
+        (3, 2) => TableSide::Left,
+        (4, 1) => TableSide::Right,
+        (4, 2) => TableSide::Top,
+        (4, 3) => TableSide::Left,
+        _ => panic!("Invalid player id"),
+    }
+}
+
+/// Positions for played cards in the trick (center of table, clockwise diamond),
+/// indexed by `TableSide`.
 const TRICK_POSITIONS: [(f32, f32); 4] = [
-    (0.0, -CARD_SIZE.y), // Player 0 (bottom)
-    (CARD_SIZE.y, 0.0),  // Player 1 (right)
-    (0.0, CARD_SIZE.y),  // Player 2 (top)
-    (-CARD_SIZE.y, 0.0), // Player 3 (left)
+    (0.0, -CARD_SIZE.y), // Bottom
+    (CARD_SIZE.y, 0.0),  // Right
+    (0.0, CARD_SIZE.y),  // Top
+    (-CARD_SIZE.y, 0.0), // Left
 ];
 
 /// Distance between the visual representation of the player and the edge of the screen
@@ -35,19 +371,27 @@ const EDGE_MARGIN: f32 = 10.;
 /// Size of the card sprite.
 const CARD_SIZE: Vec2 = Vec2::new(32., 48.);
 
-/// Number of cards per player
-const CARDS_PER_PLAYER: usize = 10;
-
 pub struct GameLogic;
 
 impl Plugin for GameLogic {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, init_scene)
+        app.init_state::<AppState>()
+            .add_computed_state::<AtTable>()
+            .add_systems(OnEnter(AppState::Loading), init_scene)
+            .add_systems(OnEnter(AtTable), start_game)
+            .add_systems(OnExit(AtTable), leave_table)
+            .add_systems(OnEnter(AppState::GameOver), show_game_over_buttons)
+            .add_systems(OnExit(AppState::GameOver), despawn_game_over_buttons)
             .add_systems(
                 Update,
                 (
-                    move_to_target.run_if(any_with_component::<MovingTo>),
-                    handle_restart_button,
+                    finish_loading.run_if(in_state(AppState::Loading)),
+                    (
+                        move_to_target.run_if(any_with_component::<MovingTo>),
+                        check_collection_timer,
+                    )
+                        .run_if(not(in_state(AppState::Paused))),
+                    handle_restart_button.run_if(in_state(AppState::GameOver)),
                 ),
             )
             .add_systems(Last, despawn_marked.run_if(any_with_component::<ToDespawn>))
@@ -59,140 +403,302 @@ impl Plugin for GameLogic {
             .init_resource::<MarkForDespawnAndContinueId>()
             .init_resource::<CardsBeingCollected>()
             .init_resource::<CollectionTimer>()
-            .insert_resource(GameState(Game::new()))
+            .init_resource::<Variant>()
+            .init_resource::<RulesConfig>()
+            .init_resource::<GameState>()
+            .init_resource::<SyncHandsId>()
+            .init_resource::<NextTurnId>()
+            .init_resource::<DespawnDiscardedId>()
             .init_state::<Turn>()
-            .add_systems(Update, check_collection_timer);
+            .add_plugins((
+                (
+                    accusi::AccusiPlugin,
+                    auction::AuctionPlugin,
+                    daily::DailyPlugin,
+                    deck::DeckPlugin,
+                    menu::MenuPlugin,
+                    pause::PausePlugin,
+                    pending::PendingPlugin,
+                ),
+                (
+                    plates::PlatesPlugin,
+                    rules::RulesPlugin,
+                    replay::ReplayPlugin,
+                    save::SavePlugin,
+                    stats::StatsPlugin,
+                    summary::SummaryPlugin,
+                    table::TablePlugin,
+                    tricks::TricksPlugin,
+                    trump::TrumpPlugin,
+                    undo::UndoPlugin,
+                ),
+            ));
+    }
+}
+
+/// Where the application is at, from loading the assets to the end of a game.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum AppState {
+    /// The cards are being loaded.
+    #[default]
+    Loading,
+    /// The start menu, and the screens reached from it.
+    MainMenu,
+    /// A game, or a replay, is being played at the table.
+    InGame,
+    /// The game at the table is on hold.
+    Paused,
+    /// The game at the table is over.
+    GameOver,
+}
+
+/// Whether there's a game on the table, be it played, on hold or over. It's
+/// dealt when the table is reached from the start menu, and cleared when the
+/// table is left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AtTable;
+
+impl ComputedStates for AtTable {
+    type SourceStates = AppState;
+
+    fn compute(state: AppState) -> Option<Self> {
+        matches!(
+            state,
+            AppState::InGame | AppState::Paused | AppState::GameOver
+        )
+        .then_some(AtTable)
     }
 }
 
-/// System called at the beginning of the game to load assets and spawn players.
+/// System called at the beginning of the game to load assets and spawn the
+/// score and announcement displays.
 fn init_scene(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    setup_game_sys: Res<SetupGameId>,
+    deck_style: Res<deck::DeckStyle>,
 ) {
     // Load default font
     let font_handle: Handle<Font> = Default::default();
     commands.insert_resource(FontHandle(font_handle.clone()));
 
-    // Load Italian assets
-    let mut italian_assets = ItalianAssets(Vec::with_capacity(4));
-    for suit in Suit::iter() {
-        let mut cards_in_suit = Vec::with_capacity(10);
-        for rank in ItalianRank::iter() {
-            let sprite_handle =
-                asset_server.load(format!("cards/italian/card-{}-{}.png", suit, rank as u8));
-            cards_in_suit.push(sprite_handle);
-        }
-        italian_assets.0.push(cards_in_suit);
-    }
+    // Load the card faces and back of the chosen deck style.
+    let (italian_assets, card_back) = deck::load_deck(&asset_server, *deck_style);
     commands.insert_resource(italian_assets);
+    commands.insert_resource(card_back);
+    deck::spawn_deck_button(&mut commands, &FontHandle(font_handle.clone()), *deck_style);
 
-    // Load card back.
-    let sprite_handle = asset_server.load("cards/card-back1.png");
-    commands.insert_resource(CardBack(sprite_handle));
-
-    // Spawn POV player (bottom)
-    commands.spawn((
-        Name::new("Player 0"),
-        Transform {
-            translation: player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, 0),
-            ..default()
-        },
-        Player {
-            id: PlayerId::PLAYER_0,
-            cards_counter: 0,
-        },
-        Visibility::default(),
-    ));
-
-    // Spawn player 1 (right)
-    commands.spawn((
-        Name::new("Player 1"),
-        Transform {
-            translation: player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, 1),
-            ..default()
-        },
-        Player {
-            id: PlayerId::PLAYER_1,
-            cards_counter: 0,
-        },
-        Visibility::default(),
-    ));
-
-    // Spawn player 2
+    // Spawn score display
     commands.spawn((
-        Name::new("Player 2"),
-        Transform {
-            translation: player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, 2),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
             ..default()
         },
-        Player {
-            id: PlayerId::PLAYER_2,
-            cards_counter: 0,
-        },
-        Visibility::default(),
-    ));
-
-    // Spawn player 3
-    commands.spawn((
-        Name::new("Player 3"),
-        Transform {
-            translation: player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, 3),
+        Text::new(""),
+        TextFont {
+            font: font_handle.clone(),
+            font_size: 24.0,
             ..default()
         },
-        Player {
-            id: PlayerId::PLAYER_3,
-            cards_counter: 0,
-        },
-        Visibility::default(),
+        TextColor(Color::WHITE),
+        ScoreText,
+        children![
+            (TextSpan::default(), trump::TrumpText),
+            (TextSpan::default(), accusi::AccusiText),
+        ],
     ));
 
-    // Spawn score display
+    // Spawn announcements display, opposite to the score.
     commands.spawn((
         Node {
             position_type: PositionType::Absolute,
             top: Val::Px(10.0),
-            left: Val::Px(10.0),
+            right: Val::Px(10.0),
             ..default()
         },
-        Text::new("Score: 0 - 0"),
+        Text::new(""),
         TextFont {
             font: font_handle.clone(),
-            font_size: 24.0,
+            font_size: 16.0,
             ..default()
         },
         TextColor(Color::WHITE),
-        ScoreText,
+        AnnouncementText,
     ));
+}
+
+/// Moves on to the start menu once every card is loaded, or failed to load.
+fn finish_loading(
+    asset_server: Res<AssetServer>,
+    italian_assets: Res<ItalianAssets>,
+    card_back: Res<CardBack>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let loading = italian_assets
+        .0
+        .iter()
+        .flatten()
+        .chain([&card_back.0])
+        .any(|handle| {
+            matches!(
+                asset_server.load_state(handle),
+                LoadState::NotLoaded | LoadState::Loading
+            )
+        });
+    if !loading {
+        next_state.set(AppState::MainMenu);
+    }
+}
 
-    // Call system to setup game.
-    commands.run_system(setup_game_sys.0);
+/// Sits players at the table for the game chosen in the start menu, then
+/// deals.
+fn start_game(
+    mut commands: Commands,
+    game: Res<GameState>,
+    setup_game_id: Res<SetupGameId>,
+    font: Res<FontHandle>,
+    replay: Option<Res<replay::Replay>>,
+    mut score_text_query: Query<&mut Text, (With<ScoreText>, Without<AnnouncementText>)>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+) {
+    // Spawn one player per seat, the POV player being at the bottom.
+    for i in 0..game.seats() {
+        let id = PlayerId::try_from(i).unwrap();
+        commands.spawn((
+            Name::new(format!("Player {i}")),
+            Transform {
+                translation: player_position(
+                    CANVAS_SIZE.x,
+                    CANVAS_SIZE.y,
+                    table_side(game.seats(), i),
+                ),
+                ..default()
+            },
+            Player {
+                id,
+                cards_counter: 0,
+            },
+            Visibility::default(),
+        ));
+    }
+
+    if let Ok(mut text) = score_text_query.single_mut() {
+        *text = Text::new(score_text("Score", &game.score()));
+    }
+    if let Ok(mut text) = announcement_query.single_mut() {
+        *text = Text::new("");
+    }
+    // Every game is against bots, so moves can be taken back, except in the
+    // daily deal where scores are compared, and in a replay where there's
+    // nothing to take back.
+    if replay.is_none() && game.daily.is_none() {
+        undo::spawn_undo_button(&mut commands, &font);
+    }
+    if game.table_cards().is_none() {
+        tricks::spawn_trick_buttons(&mut commands, &font);
+    }
+    plates::spawn_name_plates(&mut commands, &font, &game);
+
+    commands.run_system(setup_game_id.0);
 }
 
-fn player_position(width: f32, height: f32, player_id: usize) -> Vec3 {
-    match player_id {
-        0 => Vec3 {
+/// Clears the table once the game is left, whatever point it was at.
+fn leave_table(
+    table_query: Query<
+        Entity,
+        Or<(
+            With<Player>,
+            With<StockPile>,
+            With<table::ScopaMarker>,
+            With<plates::NamePlate>,
+            With<plates::DealerMarker>,
+            (With<Card>, Without<ChildOf>),
+        )>,
+    >,
+    panel_query: Query<
+        Entity,
+        Or<(
+            With<accusi::AccusiPanel>,
+            With<auction::AuctionPanel>,
+            With<table::CapturePanel>,
+            With<trump::TrumpPanel>,
+            With<summary::SummaryPanel>,
+            With<replay::ReplayPanel>,
+            With<undo::UndoButton>,
+            With<tricks::TrickButtons>,
+            With<tricks::LastTrickPanel>,
+            With<tricks::TrickHistoryPanel>,
+        )>,
+    >,
+    mut score_text_query: Query<&mut Text, (With<ScoreText>, Without<AnnouncementText>)>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    mut commands: Commands,
+) {
+    // Cards left in hand go along with their players.
+    for entity in table_query.iter().chain(panel_query.iter()) {
+        commands.entity(entity).despawn();
+    }
+    if let Ok(mut text) = score_text_query.single_mut() {
+        *text = Text::new("");
+    }
+    announce(&mut announcement_query, String::new());
+    // Nothing left to collect, nor any bot left to wait for.
+    commands.insert_resource(CollectionTimer::default());
+    commands.insert_resource(CardsBeingCollected::default());
+    commands.remove_resource::<replay::Replay>();
+}
+
+fn player_position(width: f32, height: f32, side: TableSide) -> Vec3 {
+    match side {
+        TableSide::Bottom => Vec3 {
             x: 0.0,
             y: -height * 0.5 + EDGE_MARGIN + CARD_SIZE.y * 0.5,
             z: 0.0,
         },
-        1 => Vec3 {
+        TableSide::Right => Vec3 {
             x: width * 0.5 - EDGE_MARGIN - CARD_SIZE.y * 0.5,
             y: 0.0,
             z: 0.0,
         },
-        2 => Vec3 {
+        TableSide::Top => Vec3 {
             x: 0.0,
             y: height * 0.5 - EDGE_MARGIN - CARD_SIZE.y * 0.5,
             z: 0.0,
         },
-        3 => Vec3 {
+        TableSide::Left => Vec3 {
             x: -width * 0.5 + EDGE_MARGIN + CARD_SIZE.y * 0.5,
             y: 0.0,
             z: 0.0,
         },
-        _ => panic!("Invalid player id"),
+    }
+}
+
+/// Local transform of the card at `index` in a hand of `hand_size` cards
+/// drawn on `side`.
+fn hand_slot(side: TableSide, index: usize, hand_size: usize) -> Transform {
+    let spacing = CARD_SIZE.x * 0.5;
+    let total_width = hand_size.saturating_sub(1) as f32 * spacing;
+    let center_offset = total_width / 2.0;
+    let card_pos = spacing * index as f32;
+    let z = index as f32;
+
+    let (rotation, translation) = match side {
+        TableSide::Bottom => (Quat::IDENTITY, Vec3::new(card_pos - center_offset, 0., z)),
+        TableSide::Right => (
+            Quat::from_rotation_z(PI * 0.5),
+            Vec3::new(0., card_pos - center_offset, z),
+        ),
+        TableSide::Top => (Quat::IDENTITY, Vec3::new(-card_pos + center_offset, 0., z)),
+        TableSide::Left => (
+            Quat::from_rotation_z(-PI * 0.5),
+            Vec3::new(0., -card_pos + center_offset, z),
+        ),
+    };
+    Transform {
+        translation,
+        rotation,
+        ..default()
     }
 }
 
@@ -205,12 +711,18 @@ impl FromWorld for SetupGameId {
     }
 }
 /// One shot system that gets called after initial setup is done and every time the game has to be started.
+///
+/// When a game is replayed every hand is dealt face up, and nobody is asked
+/// to play.
 fn setup_game(
     mut commands: Commands,
     game: Res<GameState>,
+    replay: Option<Res<replay::Replay>>,
     italian_assets: Res<ItalianAssets>,
     card_back: Res<CardBack>,
+    font: Res<FontHandle>,
     mut query: Query<(Entity, &mut Player)>,
+    stock_query: Query<Entity, With<StockPile>>,
     non_pov_play_id: Res<NonPovPlayId>,
     enable_pov_id: Res<EnablePovId>,
 ) {
@@ -221,72 +733,149 @@ fn setup_game(
         players.insert(player.id.as_usize(), (entity, player));
     }
 
-    for i in 0..PLAYERS {
-        let mut cards: Vec<TressetteCard> = game.0.hand(PlayerId::try_from(i).unwrap()).to_vec();
+    for i in 0..game.seats() {
+        let player_id = PlayerId::try_from(i).unwrap();
+        let mut cards: Vec<TressetteCard> = game.hand(player_id).to_vec();
         let (entity, player) = players.get_mut(&i).unwrap();
-        if i == 0 {
-            cards.sort_by(|a, b| (a.suit() as u8).cmp(&(b.suit() as u8)).then(a.cmp(b)));
+        if replay.is_none() && game.controller(player_id) == PlayerId::PLAYER_0 {
+            cards.sort_by(pov_order);
             distribute_to_pov(
                 &mut commands,
                 &italian_assets,
                 *entity,
                 cards,
                 &mut player.cards_counter,
+                table_side(game.seats(), i),
             );
         } else {
             distribute_to_other(
                 &mut commands,
+                &italian_assets,
                 &card_back,
                 *entity,
                 cards,
                 &mut player.cards_counter,
-                i,
+                table_side(game.seats(), i),
+                replay.is_some(),
             );
         }
     }
 
-    players_play(game, &mut commands, non_pov_play_id, enable_pov_id);
+    // Cards already played to the trick, when a saved game is resumed.
+    for (i, card) in game.current_trick().iter().enumerate() {
+        if let Some(card) = card {
+            let (x, y) = TRICK_POSITIONS[table_side(game.seats(), i) as usize];
+            commands.spawn((
+                Card(*card),
+                Sprite {
+                    custom_size: Some(CARD_SIZE),
+                    image: italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone(),
+                    ..default()
+                },
+                Transform::from_xyz(x, y, 10.0),
+                CardInPlay,
+            ));
+        }
+    }
+
+    table::spawn_table_cards(&mut commands, &game, &italian_assets);
+
+    // Lay the stock (or the monte) on the free side of the table, if the
+    // variant has one.
+    for entity in stock_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    if game.stock_len() > 0 {
+        commands
+            .spawn((
+                Name::new("Stock"),
+                Sprite {
+                    custom_size: Some(CARD_SIZE),
+                    image: card_back.0.clone(),
+                    ..default()
+                },
+                Transform::from_translation(stock_position(
+                    CANVAS_SIZE.x,
+                    CANVAS_SIZE.y,
+                    game.seats(),
+                )),
+                StockPile,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text2d::new(game.stock_len().to_string()),
+                    TextFont {
+                        font: font.0.clone(),
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    Transform::from_xyz(0., -CARD_SIZE.y * 0.5 - EDGE_MARGIN, 1.),
+                    StockCount,
+                ));
+                // The trump card lies face up across the bottom of the stock.
+                if let Some(trump) = game.trump() {
+                    parent.spawn((
+                        Sprite {
+                            custom_size: Some(CARD_SIZE),
+                            image: italian_assets.0[trump.suit() as usize]
+                                [trump.rank() as usize - 1]
+                                .clone(),
+                            ..default()
+                        },
+                        Transform {
+                            translation: Vec3::new(CARD_SIZE.x * 0.5, 0., -1.),
+                            rotation: Quat::from_rotation_z(PI * 0.5),
+                            ..default()
+                        },
+                    ));
+                }
+            });
+    }
+
+    if replay.is_none() {
+        players_play(game, &mut commands, non_pov_play_id, enable_pov_id);
+    }
 }
 
-/// Spawns card entities for the POV player.
+/// Order of the cards in the POV hand: by suit, then by rank.
+fn pov_order(a: &TressetteCard, b: &TressetteCard) -> Ordering {
+    (a.suit() as u8).cmp(&(b.suit() as u8)).then(a.cmp(b))
+}
+
+/// Position of the stock (or of the monte), on the side of the table nobody
+/// sits at, or between the left and top seats when all of them are taken.
+fn stock_position(width: f32, height: f32, seats: usize) -> Vec3 {
+    match seats {
+        3 => player_position(width, height, TableSide::Top),
+        4 => {
+            (player_position(width, height, TableSide::Left)
+                + player_position(width, height, TableSide::Top))
+                * 0.5
+        }
+        _ => player_position(width, height, TableSide::Left),
+    }
+}
+
+/// Spawns card entities for the POV player, or for the dummy they play.
 fn distribute_to_pov(
     commands: &mut Commands,
     italian_assets: &Res<ItalianAssets>,
     entity: Entity,
     cards: Vec<TressetteCard>,
     card_counter: &mut usize,
+    side: TableSide,
 ) {
-    let spacing = CARD_SIZE.x * 0.5;
-    let num_cards = CARDS_PER_PLAYER;
-    let total_width = (num_cards - 1) as f32 * spacing;
-    let center_offset = total_width / 2.0;
-
+    let hand_size = cards.len();
     let cards_ids: Vec<_> = cards
         .iter()
         .map(|card| {
-            let image_handle =
-                italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone();
-            let id = commands
-                .spawn(Cardbundle {
-                    card: Card(*card),
-                    sprite: Sprite {
-                        custom_size: Some(CARD_SIZE),
-                        image: image_handle,
-                        ..default()
-                    },
-                    transform: Transform {
-                        translation: Vec3 {
-                            x: spacing * *card_counter as f32 - center_offset,
-                            z: *card_counter as f32,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    pickable: Pickable::default(),
-                    pov: PovCard,
-                })
-                .observe(select_play_card)
-                .id();
+            let id = spawn_pov_card(
+                commands,
+                italian_assets,
+                *card,
+                hand_slot(side, *card_counter, hand_size),
+            );
             *card_counter += 1;
             id
         })
@@ -294,62 +883,57 @@ fn distribute_to_pov(
     commands.entity(entity).add_children(&cards_ids);
 }
 
-/// Spawn card entities for non POV players.
+/// Spawns a single face up, pickable card for the POV player to play.
+fn spawn_pov_card(
+    commands: &mut Commands,
+    italian_assets: &ItalianAssets,
+    card: TressetteCard,
+    transform: Transform,
+) -> Entity {
+    let image_handle = italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone();
+    commands
+        .spawn(Cardbundle {
+            card: Card(card),
+            sprite: Sprite {
+                custom_size: Some(CARD_SIZE),
+                image: image_handle,
+                ..default()
+            },
+            transform,
+            pickable: Pickable::default(),
+            pov: PovCard,
+        })
+        .observe(select_play_card)
+        .observe(auction::select_exchange_card)
+        .id()
+}
+
+/// Spawn card entities for non POV players, face down unless `face_up`.
 fn distribute_to_other(
     commands: &mut Commands,
+    italian_assets: &ItalianAssets,
     card_back: &Res<CardBack>,
     entity: Entity,
     cards: Vec<TressetteCard>,
     card_counter: &mut usize,
-    player_id: usize,
+    side: TableSide,
+    face_up: bool,
 ) {
-    let spacing = CARD_SIZE.x * 0.5;
-    let num_cards = CARDS_PER_PLAYER;
-    let total_width = (num_cards - 1) as f32 * spacing;
-    let center_offset = total_width / 2.0;
-
+    let hand_size = cards.len();
     let cards_ids: Vec<_> = cards
         .iter()
         .map(|card| {
-            let card_pos = spacing * *card_counter as f32;
-            let (rotation, translation) = match player_id {
-                1 => (
-                    Quat::from_rotation_z(PI * 0.5),
-                    Vec3 {
-                        x: 0.,
-                        y: card_pos - center_offset,
-                        z: *card_counter as f32,
-                    },
-                ),
-                2 => (
-                    Quat::IDENTITY,
-                    Vec3 {
-                        x: -card_pos + center_offset,
-                        y: 0.,
-                        z: *card_counter as f32,
-                    },
-                ),
-                3 => (
-                    Quat::from_rotation_z(-PI * 0.5),
-                    Vec3 {
-                        x: 0.,
-                        y: -card_pos + center_offset,
-                        z: *card_counter as f32,
-                    },
-                ),
-                _ => panic!("This should never happen"),
-            };
             let id = commands
                 .spawn((
                     Card(*card),
-                    Transform {
-                        translation,
-                        rotation,
-                        ..default()
-                    },
+                    hand_slot(side, *card_counter, hand_size),
                     Sprite {
                         custom_size: Some(CARD_SIZE),
-                        image: card_back.0.clone(),
+                        image: if face_up {
+                            italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone()
+                        } else {
+                            card_back.0.clone()
+                        },
                         ..default()
                     },
                 ))
@@ -360,8 +944,185 @@ fn distribute_to_other(
         .collect();
     commands.entity(entity).add_children(&cards_ids);
 }
+
+#[derive(Resource)]
+struct SyncHandsId(SystemId);
+impl FromWorld for SyncHandsId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(sync_hands);
+        SyncHandsId(id)
+    }
+}
+
+/// One shot system that brings the card entities in hand in line with the
+/// game, after cards changed hands outside of a trick: drawn from the stock,
+/// taken from the monte, exchanged or discarded.
+///
+/// Cards coming from the stock fly to their new owner, face up if the game
+/// shows them to everybody, cards passed between players keep their face, discarded cards
+/// fly off to their owner and every hand that changed is laid out again.
+fn sync_hands(
+    mut commands: Commands,
+    game: Res<GameState>,
+    italian_assets: Res<ItalianAssets>,
+    card_back: Res<CardBack>,
+    mut players: Query<(Entity, &mut Player, &Transform, Option<&Children>)>,
+    hand_cards: Query<
+        (&Card, &Sprite, &GlobalTransform),
+        (Without<CardInPlay>, Without<ToDespawn>),
+    >,
+    stock_query: Query<Entity, With<StockPile>>,
+    mut stock_count_query: Query<&mut Text2d, With<StockCount>>,
+    despawn_discarded_id: Res<DespawnDiscardedId>,
+) {
+    let seats = game.seats();
+    let held: Vec<(Entity, Vec<(Entity, TressetteCard)>)> = players
+        .iter()
+        .map(|(entity, _, _, children)| {
+            let cards = children
+                .into_iter()
+                .flatten()
+                .filter_map(|child| {
+                    hand_cards
+                        .get(*child)
+                        .ok()
+                        .map(|(card, ..)| (*child, card.0))
+                })
+                .collect();
+            (entity, cards)
+        })
+        .collect();
+    let holder_of = |card: TressetteCard| {
+        held.iter()
+            .find_map(|(_, cards)| cards.iter().find(|(_, c)| *c == card).map(|(e, _)| *e))
+    };
+    let in_any_hand = |card: TressetteCard| {
+        (0..seats).any(|i| game.hand(PlayerId::try_from(i).unwrap()).contains(&card))
+    };
+
+    for (entity, mut player, transform, _) in players.iter_mut() {
+        let side = table_side(seats, player.id.as_usize());
+        let pov_hand = game.controller(player.id) == PlayerId::PLAYER_0;
+        let target = game.hand(player.id);
+        let own: &[(Entity, TressetteCard)] = held
+            .iter()
+            .find(|(e, _)| *e == entity)
+            .map(|(_, cards)| cards.as_slice())
+            .unwrap_or_default();
+
+        // Cards no longer in anybody's hand have been discarded.
+        for (card_entity, _) in own.iter().filter(|(_, c)| !in_any_hand(*c)) {
+            commands
+                .entity(*card_entity)
+                .remove_parent_in_place()
+                .remove::<Selected>()
+                .insert((
+                    Discarded,
+                    MovingTo {
+                        target: transform.translation,
+                        speed: CARD_SPEED,
+                        on_arrival: Some(despawn_discarded_id.0),
+                    },
+                ));
+        }
+
+        let kept = own.iter().filter(|(_, c)| target.contains(c));
+        let arrived = target
+            .iter()
+            .filter(|card| !own.iter().any(|(_, c)| c == *card));
+        let mut hand: Vec<(Option<Entity>, TressetteCard)> = kept
+            .map(|(entity, card)| (Some(*entity), *card))
+            .chain(arrived.map(|card| (None, *card)))
+            .collect();
+        if hand.len() == own.len() && hand.iter().all(|(entity, _)| entity.is_some()) {
+            continue;
+        }
+        if pov_hand {
+            hand.sort_by(|a, b| pov_order(&a.1, &b.1));
+        }
+
+        let hand_size = hand.len();
+        for (index, (card_entity, card)) in hand.into_iter().enumerate() {
+            let slot = hand_slot(side, index, hand_size);
+            let card_entity = card_entity.unwrap_or_else(|| {
+                // Start from the card's previous owner, or from the stock.
+                let previous = holder_of(card);
+                let (from, image) = match previous.and_then(|e| hand_cards.get(e).ok()) {
+                    Some((_, sprite, global)) => (global.translation(), sprite.image.clone()),
+                    None if game.shows_drawn_cards() => (
+                        stock_position(CANVAS_SIZE.x, CANVAS_SIZE.y, seats),
+                        italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone(),
+                    ),
+                    None => (
+                        stock_position(CANVAS_SIZE.x, CANVAS_SIZE.y, seats),
+                        card_back.0.clone(),
+                    ),
+                };
+                if let Some(previous) = previous {
+                    commands.entity(previous).despawn();
+                }
+                let start = Transform {
+                    translation: (from - transform.translation).with_z(slot.translation.z),
+                    ..slot
+                };
+                let new_card = if pov_hand {
+                    spawn_pov_card(&mut commands, &italian_assets, card, start)
+                } else {
+                    commands
+                        .spawn((
+                            Card(card),
+                            start,
+                            Sprite {
+                                custom_size: Some(CARD_SIZE),
+                                image,
+                                ..default()
+                            },
+                        ))
+                        .id()
+                };
+                commands.entity(entity).add_child(new_card);
+                new_card
+            });
+            commands.entity(card_entity).insert(MovingTo {
+                target: slot.translation,
+                speed: CARD_SPEED,
+                on_arrival: None,
+            });
+        }
+        player.cards_counter = hand_size;
+    }
+
+    if game.stock_len() == 0 {
+        for entity in stock_query.iter() {
+            commands.entity(entity).despawn();
+        }
+    } else if let Ok(mut text) = stock_count_query.single_mut() {
+        *text = Text2d::new(game.stock_len().to_string());
+    }
+}
+
+#[derive(Resource)]
+struct DespawnDiscardedId(SystemId);
+impl FromWorld for DespawnDiscardedId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(despawn_discarded);
+        DespawnDiscardedId(id)
+    }
+}
+
+/// Called when a discarded card reaches its owner.
+fn despawn_discarded(
+    mut commands: Commands,
+    query: Query<Entity, (With<Discarded>, Without<MovingTo>)>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(ToDespawn);
+    }
+}
+
 const CARD_SPEED: f32 = 1000.0;
 const COLLECTION_DELAY: f32 = 2.;
+const BOT_DELAY: f32 = 1.;
 const SELECTION_OFFSET: f32 = 20.;
 
 /// This is called when the POV player clicks on one of their cards.
@@ -374,6 +1135,7 @@ fn select_play_card(
     >,
     handle_effect_id: Res<HandleEffectId>,
     mut unselected_card_query: Query<(&mut Transform, &Card), (With<Playable>, Without<Selected>)>,
+    font: Res<FontHandle>,
     mut commands: Commands,
     state: Res<State<Turn>>,
 ) {
@@ -383,29 +1145,24 @@ fn select_play_card(
 
     let click_event = click.event();
     let clicked_card = click_event.entity;
+    let side = table_side(game.seats(), game.current_player().as_usize());
 
     for (selected_entity, mut selected_transform, card) in selected_card_query.iter_mut() {
         if selected_entity != clicked_card {
-            selected_transform.translation.y -= SELECTION_OFFSET;
+            selected_transform.translation.y -= selection_offset(side);
             commands.entity(selected_entity).remove::<Selected>();
         } else {
-            match game.0.play_card(card.0) {
+            // Let the player choose what to capture when there's more than one way.
+            let options = game.capture_options(card.0);
+            if options.len() > 1 {
+                table::show_capture_choice(&mut commands, &font, clicked_card, card.0, options);
+                commands.set_state(Turn::NonPovTurn);
+                continue;
+            }
+            match game.play_card(card.0) {
                 Ok(_effect) => {
-                    // Move to trick position
-                    let (x, y) = TRICK_POSITIONS[PlayerId::PLAYER_0.as_usize()];
-                    commands
-                        .entity(clicked_card)
-                        .remove::<Playable>()
-                        .remove::<Selected>()
-                        .remove::<PovCard>()
-                        .insert(CardInPlay)
-                        .remove_parent_in_place()
-                        .insert(MovingTo {
-                            target: Vec3::new(x, y, 10.0),
-                            speed: CARD_SPEED,
-                            on_arrival: Some(handle_effect_id.0),
-                        });
-                    commands.set_state(Turn::NonPovTurn);
+                    game.record(Move::Play(card.0));
+                    send_pov_card_to_trick(&mut commands, clicked_card, side, &handle_effect_id);
                 }
                 Err(e) => {
                     warn!("Invalid play: {:?}", e);
@@ -414,11 +1171,45 @@ fn select_play_card(
         }
     }
     if let Ok((mut transform, _card)) = unselected_card_query.get_mut(clicked_card) {
-        transform.translation.y += SELECTION_OFFSET;
+        transform.translation.y += selection_offset(side);
         commands.entity(clicked_card).insert(Selected);
     }
 }
 
+/// How far a selected card is lifted towards the centre of the table from a
+/// hand drawn on `side`.
+fn selection_offset(side: TableSide) -> f32 {
+    if side == TableSide::Top {
+        -SELECTION_OFFSET
+    } else {
+        SELECTION_OFFSET
+    }
+}
+
+/// Sends a card the POV player just played, from the hand on `side`, to its
+/// trick position.
+fn send_pov_card_to_trick(
+    commands: &mut Commands,
+    entity: Entity,
+    side: TableSide,
+    handle_effect_id: &HandleEffectId,
+) {
+    let (x, y) = TRICK_POSITIONS[side as usize];
+    commands
+        .entity(entity)
+        .remove::<Playable>()
+        .remove::<Selected>()
+        .remove::<PovCard>()
+        .insert(CardInPlay)
+        .remove_parent_in_place()
+        .insert(MovingTo {
+            target: Vec3::new(x, y, 10.0),
+            speed: CARD_SPEED,
+            on_arrival: Some(handle_effect_id.0),
+        });
+    commands.set_state(Turn::NonPovTurn);
+}
+
 fn move_to_target(
     mut query: Query<(Entity, &mut Transform, &MovingTo)>,
     time: Res<Time>,
@@ -453,73 +1244,83 @@ fn handle_effect(
     non_pov_play_id: Res<NonPovPlayId>,
     enable_pov_id: Res<EnablePovId>,
     collect_cards_id: Res<CollectCardsId>,
-    font: Res<FontHandle>,
+    settle_table_id: Res<table::SettleTableId>,
+    sync_hands_id: Res<SyncHandsId>,
     mut commands: Commands,
     game: Res<GameState>,
     mut score_text_query: Query<&mut Text, With<ScoreText>>,
 ) {
-    let effect = game.0.history().last().unwrap().1;
+    let effect = game.last_effect().unwrap();
+    if game.table_cards().is_some() {
+        commands.run_system(settle_table_id.0);
+    }
     match effect {
-        shuftlib::tressette::MoveEffect::CardPlayed => {
+        MoveEffect::CardPlayed => {
+            // New cards may have been dealt once the hands were empty.
+            commands.run_system(sync_hands_id.0);
             players_play(game, &mut commands, non_pov_play_id, enable_pov_id);
         }
-        shuftlib::tressette::MoveEffect::TrickCompleted { .. } => {
+        MoveEffect::TrickCompleted { .. } => {
             commands.insert_resource(CollectionTimer::new(collect_cards_id.0));
         }
-        shuftlib::tressette::MoveEffect::HandComplete {
-            trick_winner: _,
-            score,
-        } => {
+        MoveEffect::HandComplete { trick_winner: _ } => {
             if let Ok(mut text) = score_text_query.single_mut() {
-                *text = Text::new(format!("Score: {} - {}", score.0, score.1));
+                *text = Text::new(score_text("Score", &game.score()));
             }
             commands.insert_resource(CollectionTimer::new(collect_cards_id.0));
         }
-        shuftlib::tressette::MoveEffect::GameOver {
-            trick_winner: _,
-            final_score,
-        } => {
+        MoveEffect::GameOver { trick_winner: _ } => {
             commands.insert_resource(CollectionTimer::new(collect_cards_id.0));
             if let Ok(mut text) = score_text_query.single_mut() {
-                *text = Text::new(format!(
-                    "Final Score: {} - {}",
-                    final_score.0, final_score.1
-                ));
+                *text = Text::new(score_text("Final Score", &game.score()));
             }
-            // Spawn restart button
-            commands
-                .spawn((
-                    Node {
-                        position_type: PositionType::Absolute,
-                        top: Val::Px(50.0),
-                        left: Val::Px(10.0),
-                        ..default()
-                    },
-                    Interaction::None,
-                    BackgroundColor(Color::srgb(0.5, 0.5, 0.5)),
-                    RestartButton,
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Restart Game"),
-                        TextFont {
-                            font: font.0.clone(),
-                            font_size: 24.0,
-                            ..default()
-                        },
-                        TextColor(Color::WHITE),
-                    ));
-                });
         }
     }
 }
 
+/// Shows the buttons to play again or go back to the start menu.
+fn show_game_over_buttons(font: Res<FontHandle>, mut commands: Commands) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    panel::spawn_lone_button(
+        &mut commands,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(50.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        &text_font,
+        "Restart Game",
+        (RestartButton, GameOverButton),
+    );
+    menu::spawn_back_to_menu_button(&mut commands, &font);
+}
+
+/// Clears what was shown once the game was over, as soon as the player moves
+/// on.
+fn despawn_game_over_buttons(
+    query: Query<Entity, Or<(With<GameOverButton>, With<summary::SummaryPanel>)>>,
+    mut commands: Commands,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
 #[derive(Component)]
 struct ToDespawn;
 
 #[derive(Component)]
 struct RestartButton;
 
+/// Buttons shown once the game is over, all gone as soon as one is pressed.
+#[derive(Component)]
+struct GameOverButton;
+
 #[derive(Resource)]
 struct CollectCardsId(SystemId);
 impl FromWorld for CollectCardsId {
@@ -536,7 +1337,7 @@ fn collect_cards(
     mut cards_being_collected: ResMut<CardsBeingCollected>,
     mut commands: Commands,
 ) {
-    let effect = game.0.history().last().unwrap().1;
+    let effect = game.last_effect().unwrap();
     let winner = match effect {
         MoveEffect::TrickCompleted { winner } => Some(winner),
         MoveEffect::HandComplete { trick_winner, .. } => Some(trick_winner),
@@ -548,7 +1349,11 @@ fn collect_cards(
         let mut count = 0;
         for card in query.iter() {
             commands.entity(card).insert(MovingTo {
-                target: player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, winner.as_usize()),
+                target: player_position(
+                    CANVAS_SIZE.x,
+                    CANVAS_SIZE.y,
+                    table_side(game.seats(), winner.as_usize()),
+                ),
                 speed: CARD_SPEED,
                 on_arrival: Some(mark_for_despawn_and_continue_id.0),
             });
@@ -569,8 +1374,14 @@ struct CollectionTimer {
 
 impl CollectionTimer {
     fn new(callback: SystemId) -> Self {
+        Self::after(COLLECTION_DELAY, callback)
+    }
+
+    /// Runs `callback` after `seconds`, e.g. to give players the time to read
+    /// what a bot did.
+    fn after(seconds: f32, callback: SystemId) -> Self {
         Self {
-            timer: Some(Timer::from_seconds(COLLECTION_DELAY, TimerMode::Once)),
+            timer: Some(Timer::from_seconds(seconds, TimerMode::Once)),
             callback: Some(callback),
         }
     }
@@ -606,7 +1417,9 @@ fn mark_for_despawn_and_continue(
     non_pov_play_id: Res<NonPovPlayId>,
     enable_pov_id: Res<EnablePovId>,
     setup_game_id: Res<SetupGameId>,
+    sync_hands_id: Res<SyncHandsId>,
     game: Res<GameState>,
+    font: Res<FontHandle>,
     mut cards_being_collected: ResMut<CardsBeingCollected>,
     mut commands: Commands,
 ) {
@@ -623,15 +1436,25 @@ fn mark_for_despawn_and_continue(
         }
 
         // After marking cards for despawn, check what to do next based on the effect
-        if let Some((_move, effect)) = game.0.history().last() {
+        if let Some(effect) = game.last_effect() {
             match effect {
                 MoveEffect::TrickCompleted { winner: _ } => {
+                    commands.run_system(sync_hands_id.0);
                     players_play(game, &mut commands, non_pov_play_id, enable_pov_id);
                 }
+                MoveEffect::HandComplete { .. } if !game.hand_summary().is_empty() => {
+                    summary::show_summary(&mut commands, &font, &game, false);
+                }
                 MoveEffect::HandComplete { .. } => {
                     commands.run_system(setup_game_id.0);
                 }
-                _ => {}
+                MoveEffect::GameOver { .. } => {
+                    if !game.hand_summary().is_empty() {
+                        summary::show_summary(&mut commands, &font, &game, true);
+                    }
+                    commands.set_state(AppState::GameOver);
+                }
+                MoveEffect::CardPlayed => {}
             }
         }
     }
@@ -644,33 +1467,33 @@ fn despawn_marked(mut commands: Commands, query: Query<Entity, With<ToDespawn>>)
 }
 
 fn handle_restart_button(
-    interaction_query: Query<(Entity, &Interaction), (Changed<Interaction>, With<RestartButton>)>,
-    setup_game_id: Res<SetupGameId>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
     mut game: ResMut<GameState>,
-    mut score_text_query: Query<&mut Text, With<ScoreText>>,
-    card_query: Query<Entity, With<Card>>,
+    variant: Res<Variant>,
+    rules: Res<RulesConfig>,
     mut commands: Commands,
 ) {
-    for (entity, interaction) in interaction_query.iter() {
+    for interaction in interaction_query.iter() {
         if *interaction == Interaction::Pressed {
-            // Mark all cards for despawn
-            for card_entity in card_query.iter() {
-                commands.entity(card_entity).insert(ToDespawn);
-            }
-            // Reset game state
-            *game = GameState(Game::new());
-            // Update score text
-            if let Ok(mut text) = score_text_query.single_mut() {
-                *text = Text::new("Score: 0 - 0");
-            }
-            // Despawn the button
-            commands.entity(entity).despawn();
-            // Restart game
-            commands.run_system(setup_game_id.0);
+            // A scenario starts over from its deal rather than a new shuffle.
+            *game = if game.deal.is_some() {
+                game.restart()
+            } else {
+                GameState::new(*variant, *rules)
+            };
+            redeal(&mut commands);
+            commands.set_state(AppState::InGame);
         }
     }
 }
 
+/// Clears the table, whatever point the game on it was at, and deals the game
+/// in [`GameState`] on it.
+fn redeal(commands: &mut Commands) {
+    commands.run_system_cached(leave_table);
+    commands.run_system_cached(start_game);
+}
+
 /// One shot system that determines whether it's the POV player's turn or not
 /// and calls the correct system to handle the play operations.
 fn players_play(
@@ -679,13 +1502,50 @@ fn players_play(
     non_pov_play_id: Res<NonPovPlayId>,
     enable_pov_id: Res<EnablePovId>,
 ) {
-    if game.0.current_player() != PlayerId::PLAYER_0 {
+    if game.controller(game.current_player()) != PlayerId::PLAYER_0 {
         commands.run_system(non_pov_play_id.0)
     } else {
         commands.run_system(enable_pov_id.0);
     }
 }
 
+#[derive(Resource)]
+struct NextTurnId(SystemId);
+impl FromWorld for NextTurnId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(next_turn);
+        NextTurnId(id)
+    }
+}
+
+/// One shot system handing the turn to whoever has to act next.
+fn next_turn(
+    game: Res<GameState>,
+    mut commands: Commands,
+    non_pov_play_id: Res<NonPovPlayId>,
+    enable_pov_id: Res<EnablePovId>,
+) {
+    players_play(game, &mut commands, non_pov_play_id, enable_pov_id);
+}
+
+fn announce(query: &mut Query<&mut Text, With<AnnouncementText>>, message: String) {
+    if let Ok(mut text) = query.single_mut() {
+        *text = Text::new(message);
+    }
+}
+
+/// Side of the score the POV player's points are on.
+const POV_SIDE: usize = 0;
+
+/// How players are named in announcements.
+fn player_label(player: PlayerId) -> String {
+    if player == PlayerId::PLAYER_0 {
+        "You".to_string()
+    } else {
+        format!("Player {player}")
+    }
+}
+
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
 enum Turn {
     #[default]
@@ -706,8 +1566,29 @@ fn enable_pov(
     game: Res<GameState>,
     mut commands: Commands,
     mut query: Query<(Entity, &Card, &mut Sprite), With<PovCard>>,
+    pov_auction_id: Res<auction::PovAuctionId>,
+    pov_trump_id: Res<trump::PovTrumpId>,
+    pov_accusi_id: Res<accusi::PovAccusiId>,
 ) {
-    let playable = game.0.legal_cards();
+    match game.pending_phase() {
+        Some(
+            PendingPhase::Bid
+            | PendingPhase::CallThree
+            | PendingPhase::GiveBack { .. }
+            | PendingPhase::Discard { .. },
+        ) => {
+            commands.run_system(pov_auction_id.0);
+            return;
+        }
+        Some(PendingPhase::Trump) => {
+            commands.run_system(pov_trump_id.0);
+            return;
+        }
+        Some(PendingPhase::Accusi) => commands.run_system(pov_accusi_id.0),
+        None => {}
+    }
+
+    let playable = game.legal_cards();
     for (entity, card, mut sprite) in query.iter_mut() {
         if playable.contains(&card.0) {
             commands.entity(entity).insert(Playable);
@@ -736,23 +1617,31 @@ fn non_pov_play(
     handle_effect_id: Res<HandleEffectId>,
     italian_assets: Res<ItalianAssets>,
     mut query: Query<(Entity, &mut Sprite, &Card)>,
+    bot_pending_id: Res<pending::BotPendingId>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
 ) {
-    let legal_cards = game.0.legal_cards();
+    match game.pending_phase() {
+        // Accusi are declared along with the first card played.
+        Some(PendingPhase::Accusi) => accusi::bot_accusi(&mut game, &mut announcement_query),
+        Some(_) => {
+            commands.insert_resource(CollectionTimer::after(BOT_DELAY, bot_pending_id.0));
+            return;
+        }
+        None => {}
+    }
+
+    let legal_cards = game.legal_cards();
     if let Some(card) = legal_cards.first() {
-        let num_played = game
-            .0
-            .current_trick()
-            .iter()
-            .filter(|c| c.is_some())
-            .count();
-        let player_index = (game.0.trick_leader().as_usize() + num_played) % 4;
-        match game.0.play_card(*card) {
+        let num_played = game.current_trick().iter().filter(|c| c.is_some()).count();
+        let player_index = (game.trick_leader().as_usize() + num_played) % game.seats();
+        match game.play_card(*card) {
             Ok(_effect) => {
+                game.record(Move::Play(*card));
                 // Move to trick position and show face
                 if let Some((entity, mut sprite, _)) =
                     query.iter_mut().find(|(_, _, c)| c.0 == *card)
                 {
-                    let (x, y) = TRICK_POSITIONS[player_index];
+                    let (x, y) = TRICK_POSITIONS[table_side(game.seats(), player_index) as usize];
                     // Change to face-up sprite
                     sprite.image =
                         italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone();
@@ -792,6 +1681,18 @@ struct CardInPlay;
 #[derive(Component)]
 struct ScoreText;
 
+#[derive(Component)]
+struct StockPile;
+
+#[derive(Component)]
+struct StockCount;
+
+#[derive(Component)]
+struct Discarded;
+
+#[derive(Component)]
+struct AnnouncementText;
+
 #[derive(Component)]
 struct MovingTo {
     target: Vec3,
@@ -0,0 +1,193 @@
+//! Declaration of accusi on the first turn of each hand of Tressette, and
+//! the points declared in the ongoing hand next to the score.
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Val},
+};
+use shuftlib::{core::italian::ItalianRank, trick_taking::PlayerId};
+
+use super::{
+    AnnouncementText, AppState, FontHandle, GameState, Turn, announce,
+    panel::{panel_node, spawn_button, spawn_panel},
+    pending, player_label, suit_name,
+};
+use crate::games::{PendingPhase, accusi::Accuso, record::Move};
+
+pub(super) struct AccusiPlugin;
+
+impl Plugin for AccusiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                handle_accuso_buttons.run_if(in_state(AppState::InGame)),
+                update_accusi_text.run_if(resource_changed::<GameState>),
+            ),
+        )
+        .add_systems(OnEnter(Turn::NonPovTurn), despawn_accusi_panel)
+        .init_resource::<PovAccusiId>();
+    }
+}
+
+/// Shows the points declared in the ongoing hand, next to the score.
+#[derive(Component)]
+pub(super) struct AccusiText;
+
+#[derive(Component)]
+pub(super) struct AccusiPanel;
+
+/// Declares the accuso when pressed.
+#[derive(Component)]
+struct AccusoButton(Accuso);
+
+/// Name of an accuso as shown to players, e.g. "Buongioco of three Aces".
+fn accuso_name(accuso: Accuso) -> String {
+    match accuso {
+        Accuso::Napoletana(suit) => format!("Napoletana of {}", suit_name(suit)),
+        Accuso::Buongioco { rank, count } => {
+            let count = if count == 4 { "four" } else { "three" };
+            let rank = match rank {
+                ItalianRank::Ace => "Aces",
+                ItalianRank::Two => "Twos",
+                _ => "Threes",
+            };
+            format!("Buongioco of {count} {rank}")
+        }
+    }
+}
+
+pub(super) fn accuso_message(player: PlayerId, accuso: Accuso) -> String {
+    format!(
+        "{}: {} ({})",
+        player_label(player),
+        accuso_name(accuso),
+        accuso.points()
+    )
+}
+
+#[derive(Resource)]
+pub(super) struct PovAccusiId(pub(super) SystemId);
+impl FromWorld for PovAccusiId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(pov_accusi);
+        PovAccusiId(id)
+    }
+}
+
+/// One shot system offering the POV player the accusi they can declare, if
+/// any, alongside the cards they can play.
+fn pov_accusi(game: Res<GameState>, font: Res<FontHandle>, mut commands: Commands) {
+    if game.pending_phase() != Some(PendingPhase::Accusi) {
+        return;
+    }
+    let accusi: Vec<Accuso> = game
+        .pending_moves()
+        .into_iter()
+        .filter_map(|mv| match mv {
+            Move::Declare(accuso) => Some(accuso),
+            _ => None,
+        })
+        .collect();
+
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        panel_node(Val::Percent(35.0), Val::Px(10.0)),
+        AccusiPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new("Declare"),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        for accuso in accusi {
+            spawn_button(
+                parent,
+                &text_font,
+                format!("{} ({})", accuso_name(accuso), accuso.points()),
+                AccusoButton(accuso),
+            );
+        }
+    });
+}
+
+fn handle_accuso_buttons(
+    interaction_query: Query<(Entity, &Interaction, &AccusoButton), Changed<Interaction>>,
+    panel_query: Query<Entity, With<AccusiPanel>>,
+    mut game: ResMut<GameState>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    mut commands: Commands,
+) {
+    for (entity, interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match pending::make(&mut game, Move::Declare(button.0)) {
+            Ok((message, _)) => {
+                announce(&mut announcement_query, message);
+                commands.entity(entity).despawn();
+                if game.pending_phase() != Some(PendingPhase::Accusi) {
+                    for entity in panel_query.iter() {
+                        commands.entity(entity).despawn();
+                    }
+                }
+            }
+            Err(e) => warn!("Invalid accuso: {:?}", e),
+        }
+    }
+}
+
+/// Declares every accuso a bot holds, announcing them to the table.
+pub(super) fn bot_accusi(
+    game: &mut GameState,
+    announcement_query: &mut Query<&mut Text, With<AnnouncementText>>,
+) {
+    let mut messages = Vec::new();
+    while game.pending_phase() == Some(PendingPhase::Accusi) {
+        let Some(mv) = game.bot_move() else {
+            break;
+        };
+        match pending::make(game, mv) {
+            Ok((message, _)) => messages.push(message),
+            Err(e) => {
+                warn!("AI invalid accuso: {:?}", e);
+                break;
+            }
+        }
+    }
+    if !messages.is_empty() {
+        announce(announcement_query, messages.join("\n"));
+    }
+}
+
+/// The chance to declare is gone once the POV player played.
+fn despawn_accusi_panel(mut commands: Commands, panel_query: Query<Entity, With<AccusiPanel>>) {
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Keeps the points declared in the ongoing hand in line with the game.
+fn update_accusi_text(game: Res<GameState>, mut query: Query<&mut TextSpan, With<AccusiText>>) {
+    let declared = game.declared();
+    let label = if declared.is_empty() {
+        String::new()
+    } else {
+        let mut points = [0; 2];
+        for (player, accuso) in declared {
+            points[player.as_usize() % 2] += accuso.points();
+        }
+        format!("   Accusi: {} - {}", points[0], points[1])
+    };
+    for mut span in query.iter_mut() {
+        **span = label.clone();
+    }
+}
@@ -0,0 +1,200 @@
+//! Auction and exchange of cards that come before the play in Terziglio.
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Val},
+};
+use shuftlib::{tressette::TressetteCard, trick_taking::PlayerId};
+
+use super::{
+    AnnouncementText, AppState, Card, FontHandle, GameState, NextTurnId, PovCard, SELECTION_OFFSET,
+    Selected, SetupGameId, SyncHandsId, Turn, announce,
+    panel::{panel_node, spawn_button, spawn_panel},
+    pending, player_label, suit_name,
+};
+use crate::games::{PendingPhase, record::Move, terziglio::Bid};
+
+pub(super) struct AuctionPlugin;
+
+impl Plugin for AuctionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_auction_buttons.run_if(in_state(AppState::InGame)),
+        )
+        .init_resource::<PovAuctionId>();
+    }
+}
+
+#[derive(Component)]
+pub(super) struct AuctionPanel;
+
+/// What pressing an auction button does.
+#[derive(Component, Clone)]
+enum AuctionButton {
+    /// Makes the move.
+    Move(Move),
+    /// Confirms the cards selected in the POV hand.
+    Confirm,
+}
+
+#[derive(Resource)]
+pub(super) struct PovAuctionId(pub(super) SystemId);
+impl FromWorld for PovAuctionId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(pov_auction);
+        PovAuctionId(id)
+    }
+}
+
+/// Label of the button making `mv`.
+fn move_label(mv: &Move) -> String {
+    match mv {
+        Move::Bid(Bid::Call) => "Call".to_string(),
+        Move::Bid(Bid::Pass) => "Pass".to_string(),
+        Move::CallThree(three) => format!("3 of {}", suit_name(three.suit())),
+        _ => String::new(),
+    }
+}
+
+/// One shot system that shows the POV player the choices of the current phase.
+fn pov_auction(
+    game: Res<GameState>,
+    font: Res<FontHandle>,
+    mut commands: Commands,
+    mut pov_cards: Query<&mut Sprite, With<PovCard>>,
+) {
+    let moves = || {
+        game.pending_moves()
+            .into_iter()
+            .map(|mv| (move_label(&mv), AuctionButton::Move(mv)))
+            .collect()
+    };
+    let (prompt, buttons): (String, Vec<(String, AuctionButton)>) = match game.pending_phase() {
+        Some(PendingPhase::Bid) => ("Your bid".to_string(), moves()),
+        Some(PendingPhase::CallThree) => ("Ask for a Three".to_string(), moves()),
+        Some(PendingPhase::GiveBack { to }) => (
+            format!("Pick a card to give to {}", player_label(to)),
+            vec![("Give".to_string(), AuctionButton::Confirm)],
+        ),
+        Some(PendingPhase::Discard { count }) => (
+            format!("Pick {count} cards to discard"),
+            vec![("Discard".to_string(), AuctionButton::Confirm)],
+        ),
+        _ => return,
+    };
+
+    // Every card can be picked during the exchange.
+    for mut sprite in pov_cards.iter_mut() {
+        sprite.color = Color::WHITE;
+    }
+    commands.set_state(Turn::NonPovTurn);
+
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        panel_node(Val::Percent(35.0), Val::Percent(35.0)),
+        AuctionPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(prompt),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        for (label, button) in buttons {
+            spawn_button(parent, &text_font, label, button);
+        }
+    });
+}
+
+fn handle_auction_buttons(
+    interaction_query: Query<(&Interaction, &AuctionButton), Changed<Interaction>>,
+    mut game: ResMut<GameState>,
+    panel_query: Query<Entity, With<AuctionPanel>>,
+    selected_query: Query<&Card, (With<PovCard>, With<Selected>)>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    card_query: Query<Entity, With<Card>>,
+    setup_game_id: Res<SetupGameId>,
+    sync_hands_id: Res<SyncHandsId>,
+    next_turn_id: Res<NextTurnId>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let selected: Vec<TressetteCard> = selected_query.iter().map(|card| card.0).collect();
+        let mv = match (button, game.pending_phase()) {
+            (AuctionButton::Move(mv), _) => mv.clone(),
+            (AuctionButton::Confirm, Some(PendingPhase::GiveBack { .. }))
+                if selected.len() == 1 =>
+            {
+                Move::GiveBack(selected[0])
+            }
+            (AuctionButton::Confirm, Some(PendingPhase::Discard { count }))
+                if selected.len() == count =>
+            {
+                Move::Discard(selected)
+            }
+            (AuctionButton::Confirm, _) => {
+                announce(&mut announcement_query, "Wrong number of cards".to_string());
+                continue;
+            }
+        };
+
+        match pending::make(&mut game, mv) {
+            Ok((message, outcome)) => {
+                for entity in panel_query.iter() {
+                    commands.entity(entity).despawn();
+                }
+                commands.set_state(Turn::NonPovTurn);
+                announce(&mut announcement_query, message);
+                pending::move_on(
+                    &mut commands,
+                    outcome,
+                    &card_query,
+                    &setup_game_id,
+                    &sync_hands_id,
+                    &next_turn_id,
+                );
+            }
+            Err(e) => warn!("Invalid auction action: {:?}", e),
+        }
+    }
+}
+
+/// This is called when the POV player clicks on one of their cards while
+/// picking cards to give away or discard.
+pub(super) fn select_exchange_card(
+    click: On<Pointer<Click>>,
+    game: Res<GameState>,
+    mut query: Query<(&mut Transform, Has<Selected>), With<PovCard>>,
+    mut commands: Commands,
+) {
+    let exchanging = matches!(
+        game.pending_phase(),
+        Some(PendingPhase::GiveBack { .. } | PendingPhase::Discard { .. })
+    );
+    if !exchanging || game.current_player() != PlayerId::PLAYER_0 {
+        return;
+    }
+
+    let clicked_card = click.event().entity;
+    if let Ok((mut transform, selected)) = query.get_mut(clicked_card) {
+        if selected {
+            transform.translation.y -= SELECTION_OFFSET;
+            commands.entity(clicked_card).remove::<Selected>();
+        } else {
+            transform.translation.y += SELECTION_OFFSET;
+            commands.entity(clicked_card).insert(Selected);
+        }
+    }
+}
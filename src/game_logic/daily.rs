@@ -0,0 +1,208 @@
+//! The daily deal: a game of Tressette dealt from a seed that only depends on
+//! the date, so that everybody playing it on the same day gets the same hands
+//! against the same bots and can compare scores afterwards.
+//!
+//! Days go by UTC, for players in different time zones to share them. The
+//! deal of a day is only dealt once: from its first move on it can only be
+//! continued, and its result is kept between sessions.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use shuftlib::tressette::Status;
+
+use super::{GameState, POV_SIDE, Variant, replay::Replay};
+use crate::{games::RulesConfig, storage};
+
+/// Key the results of the daily deals are stored under.
+const KEY: &str = "daily_results";
+
+/// Key the date of the last daily deal started is stored under.
+const STARTED_KEY: &str = "daily_started";
+
+pub(super) struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (mark_started, record_result).run_if(resource_changed::<GameState>),
+        );
+    }
+}
+
+/// How the daily deal of a day went.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct DailyResult {
+    pub(super) score: Vec<u8>,
+    pub(super) won: bool,
+}
+
+/// Days since the Unix epoch, in UTC.
+#[cfg(not(target_arch = "wasm32"))]
+fn days_since_epoch() -> i64 {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    (seconds / 86_400) as i64
+}
+
+/// Days since the Unix epoch, in UTC.
+#[cfg(target_arch = "wasm32")]
+fn days_since_epoch() -> i64 {
+    (js_sys::Date::now() / 86_400_000.).floor() as i64
+}
+
+/// Date of the day `days` after the Unix epoch, as `YYYY-MM-DD`.
+fn date(days: i64) -> String {
+    // Days are counted from the 1st of March, for leap days to come last.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Seed the deal of the day `days` after the Unix epoch is shuffled from.
+/// The days are mixed up, for the deals of two days in a row not to look
+/// alike.
+fn seed(days: i64) -> u64 {
+    let mut seed = (days as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    seed ^ (seed >> 31)
+}
+
+/// Today's date, as the daily deal is known by.
+pub(super) fn today() -> String {
+    date(days_since_epoch())
+}
+
+/// Deals today's game.
+pub(super) fn new_game() -> GameState {
+    let days = days_since_epoch();
+    GameState {
+        daily: Some(date(days)),
+        ..GameState::with_seed(Variant::Tressette, RulesConfig::default(), seed(days))
+    }
+}
+
+/// Results of the daily deals played so far, by date.
+pub(super) fn results() -> BTreeMap<String, DailyResult> {
+    let Some(contents) = storage::read(KEY) else {
+        return BTreeMap::new();
+    };
+    ron::from_str(&contents).unwrap_or_else(|e| {
+        warn!("Unreadable daily results: {e}");
+        BTreeMap::new()
+    })
+}
+
+/// Date of the last daily deal started, if any.
+fn last_started() -> Option<String> {
+    ron::from_str(&storage::read(STARTED_KEY)?).ok()
+}
+
+/// Whether today's deal was already started, and can't be dealt again.
+pub(super) fn started_today() -> bool {
+    let today = today();
+    last_started().as_ref() == Some(&today) || results().contains_key(&today)
+}
+
+/// Keeps the date of a daily deal once its first move is made, for the deal
+/// not to be dealt again from the start.
+fn mark_started(
+    game: Res<GameState>,
+    replay: Option<Res<Replay>>,
+    mut marked: Local<Option<String>>,
+) {
+    let Some(day) = &game.daily else {
+        return;
+    };
+    if replay.is_some() || game.moves.is_empty() || marked.as_ref() == Some(day) {
+        return;
+    }
+    *marked = Some(day.clone());
+    let result = ron::to_string(day)
+        .map_err(std::io::Error::other)
+        .and_then(|contents| storage::write(STARTED_KEY, &contents));
+    if let Err(e) = result {
+        warn!("Daily deal can't be marked as started: {e}");
+    }
+}
+
+/// Keeps the result of a daily deal once it's over, unless one was kept for
+/// that day already.
+fn record_result(game: Res<GameState>, replay: Option<Res<Replay>>) {
+    let (Some(day), Status::Finished { winner }) = (&game.daily, game.status()) else {
+        return;
+    };
+    if replay.is_some() {
+        return;
+    }
+    let mut results = results();
+    if results.contains_key(day) {
+        return;
+    }
+    results.insert(
+        day.clone(),
+        DailyResult {
+            score: game.score(),
+            won: usize::from(winner) == POV_SIDE,
+        },
+    );
+    let result = ron::to_string(&results)
+        .map_err(std::io::Error::other)
+        .and_then(|contents| storage::write(KEY, &contents));
+    if let Err(e) = result {
+        warn!("Daily result can't be saved: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_of_the_epoch() {
+        assert_eq!(date(0), "1970-01-01");
+    }
+
+    #[test]
+    fn date_around_the_end_of_february() {
+        assert_eq!(date(58), "1970-02-28");
+        assert_eq!(date(59), "1970-03-01");
+        assert_eq!(date(60), "1970-03-02");
+    }
+
+    #[test]
+    fn date_of_a_leap_day() {
+        assert_eq!(date(11_015), "2000-02-28");
+        assert_eq!(date(11_016), "2000-02-29");
+        assert_eq!(date(11_017), "2000-03-01");
+    }
+
+    #[test]
+    fn date_before_the_epoch() {
+        assert_eq!(date(-1), "1969-12-31");
+        assert_eq!(date(-365), "1969-01-01");
+    }
+
+    #[test]
+    fn seed_changes_every_day() {
+        for days in [-1, 0, 59, 11_016, 20_744] {
+            assert_ne!(seed(days), seed(days + 1));
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Deck styles the cards can be drawn with, and the buttons switching between
+//! them at any time: one in the corner of the screen, one in the pause menu.
+
+use std::collections::HashMap;
+
+use bevy::{
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, PositionType, Val},
+};
+use shuftlib::core::{Suit, italian::ItalianRank};
+use strum::{EnumIter, IntoEnumIterator};
+
+use super::{
+    CardBack, FontHandle, ItalianAssets,
+    panel::{spawn_button, spawn_lone_button},
+};
+
+pub(super) struct DeckPlugin;
+
+impl Plugin for DeckPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                handle_deck_button,
+                label_deck_buttons.run_if(resource_changed::<DeckStyle>),
+                swap_deck_style
+                    .run_if(resource_changed::<DeckStyle>.and(not(resource_added::<DeckStyle>))),
+            ),
+        )
+        .init_resource::<DeckStyle>();
+    }
+}
+
+/// Artwork the cards are drawn with. Each style has its own asset folder and
+/// card back.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter)]
+pub enum DeckStyle {
+    #[default]
+    Italian,
+    Napoletane,
+    Piacentine,
+    Siciliane,
+    Trevigiane,
+    French,
+}
+
+impl DeckStyle {
+    /// Name shown on the deck button.
+    pub fn name(self) -> &'static str {
+        match self {
+            DeckStyle::Italian => "Italian",
+            DeckStyle::Napoletane => "Napoletane",
+            DeckStyle::Piacentine => "Piacentine",
+            DeckStyle::Siciliane => "Siciliane",
+            DeckStyle::Trevigiane => "Trevigiane",
+            DeckStyle::French => "French",
+        }
+    }
+
+    /// Folder under `cards/` holding the faces of the deck.
+    fn folder(self) -> &'static str {
+        match self {
+            DeckStyle::Italian => "italian",
+            DeckStyle::Napoletane => "napoletane",
+            DeckStyle::Piacentine => "piacentine",
+            DeckStyle::Siciliane => "siciliane",
+            DeckStyle::Trevigiane => "trevigiane",
+            DeckStyle::French => "french",
+        }
+    }
+
+    /// Path of the card back.
+    fn back(self) -> String {
+        match self {
+            DeckStyle::Italian => "cards/card-back1.png".to_string(),
+            style => format!("cards/{}/card-back.png", style.folder()),
+        }
+    }
+
+    /// The style after this one, going back to the first after the last.
+    fn next(self) -> Self {
+        DeckStyle::iter()
+            .cycle()
+            .skip_while(|style| *style != self)
+            .nth(1)
+            .unwrap_or_default()
+    }
+}
+
+/// Loads the card faces and back of `style`.
+pub(super) fn load_deck(asset_server: &AssetServer, style: DeckStyle) -> (ItalianAssets, CardBack) {
+    let mut italian_assets = ItalianAssets(Vec::with_capacity(4));
+    for suit in Suit::iter() {
+        let mut cards_in_suit = Vec::with_capacity(10);
+        for rank in ItalianRank::iter() {
+            let sprite_handle = asset_server.load(format!(
+                "cards/{}/card-{}-{}.png",
+                style.folder(),
+                suit,
+                rank as u8
+            ));
+            cards_in_suit.push(sprite_handle);
+        }
+        italian_assets.0.push(cards_in_suit);
+    }
+    (italian_assets, CardBack(asset_server.load(style.back())))
+}
+
+/// Switches to the next deck style when pressed.
+#[derive(Component)]
+struct DeckButton;
+
+/// Spawns the button switching deck style, in the bottom right corner.
+pub(super) fn spawn_deck_button(commands: &mut Commands, font: &FontHandle, style: DeckStyle) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_lone_button(
+        commands,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            right: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        &text_font,
+        deck_label(style),
+        DeckButton,
+    );
+}
+
+fn deck_label(style: DeckStyle) -> String {
+    format!("Deck: {}", style.name())
+}
+
+/// Spawns a button switching deck style within `parent`, like the settings
+/// of the pause menu.
+pub(super) fn spawn_deck_option(
+    parent: &mut ChildSpawnerCommands,
+    font: &FontHandle,
+    style: DeckStyle,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    spawn_button(parent, &text_font, deck_label(style), DeckButton);
+}
+
+fn handle_deck_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<DeckButton>)>,
+    mut style: ResMut<DeckStyle>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            *style = style.next();
+        }
+    }
+}
+
+/// Keeps every deck button showing the style in use, whichever was pressed.
+fn label_deck_buttons(
+    style: Res<DeckStyle>,
+    button_query: Query<&Children, With<DeckButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    for children in button_query.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = deck_label(*style);
+            }
+        }
+    }
+}
+
+/// Loads the deck style just chosen and redraws every card on the table with
+/// it, face up or face down as they were.
+fn swap_deck_style(
+    style: Res<DeckStyle>,
+    asset_server: Res<AssetServer>,
+    mut italian_assets: ResMut<ItalianAssets>,
+    mut card_back: ResMut<CardBack>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    let (new_assets, new_back) = load_deck(&asset_server, *style);
+
+    let mut replacements: HashMap<AssetId<Image>, Handle<Image>> = italian_assets
+        .0
+        .iter()
+        .flatten()
+        .zip(new_assets.0.iter().flatten())
+        .map(|(old, new)| (old.id(), new.clone()))
+        .collect();
+    replacements.insert(card_back.0.id(), new_back.0.clone());
+
+    for mut sprite in sprites.iter_mut() {
+        if let Some(new) = replacements.get(&sprite.image.id()) {
+            sprite.image = new.clone();
+        }
+    }
+
+    *italian_assets = new_assets;
+    *card_back = new_back;
+}
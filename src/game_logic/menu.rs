@@ -0,0 +1,345 @@
+//! Start menu, to choose which game to play.
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, PositionType, Val},
+};
+use strum::IntoEnumIterator;
+
+use super::{
+    AppState, FontHandle, GameOverButton, GameState, Variant, daily, notation,
+    panel::{panel_node, spawn_button, spawn_colored_button, spawn_lone_button, spawn_panel},
+    replay::{Replay, ShowReplayControlsId},
+    rules::ShowRulesId,
+    save::{self, SavedGame},
+    scenario,
+    stats::ShowStatsId,
+};
+use crate::games::RulesConfig;
+
+/// Number of columns the variants are laid out in.
+const VARIANT_COLUMNS: u16 = 3;
+
+pub(super) struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                handle_menu_buttons,
+                handle_continue_button,
+                handle_daily_button,
+                handle_scenario_button,
+                handle_replay_button,
+                handle_stats_button,
+                handle_back_to_menu_button,
+            ),
+        )
+        .add_systems(OnEnter(AppState::MainMenu), show_menu)
+        .add_systems(OnExit(AppState::MainMenu), despawn_menu)
+        .init_resource::<ShowMenuId>();
+    }
+}
+
+#[derive(Component)]
+struct MenuPanel;
+
+/// Starts a game of the given variant.
+#[derive(Component)]
+struct MenuButton(Variant);
+
+/// Resumes the game saved last time.
+#[derive(Component)]
+struct ContinueButton;
+
+/// Starts today's daily deal, or continues it if it was left unfinished.
+#[derive(Component)]
+struct DailyButton;
+
+/// Starts the game set up in the scenario file.
+#[derive(Component)]
+struct ScenarioButton;
+
+/// Replays a finished game: the last one, or one read from a record.
+#[derive(Component)]
+struct ReplayButton(SavedGame);
+
+/// Shows the statistics of the games played.
+#[derive(Component)]
+struct StatsButton;
+
+#[derive(Component)]
+struct BackToMenuButton;
+
+#[derive(Resource)]
+pub(super) struct ShowMenuId(pub(super) SystemId);
+impl FromWorld for ShowMenuId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(show_menu);
+        ShowMenuId(id)
+    }
+}
+
+/// One shot system that shows a button for each game that can be played,
+/// one to continue the game left unfinished, one for the daily deal unless
+/// it was started today already and isn't the game left unfinished, one to
+/// play the scenario file, one to replay the last game finished and one to
+/// replay the game record left to be read, if any, and one to see the
+/// statistics.
+fn show_menu(font: Res<FontHandle>, mut commands: Commands) {
+    let saved = save::load();
+    let today = daily::today();
+    let daily_saved = saved
+        .as_ref()
+        .is_some_and(|saved| saved.daily.as_ref() == Some(&today));
+    let scenario = scenario::load();
+    let replays = [
+        save::load_last_game().map(|game| (format!("Replay last {}", game.variant.name()), game)),
+        notation::read_record()
+            .map(|game| (format!("Replay {} record", game.variant.name()), game)),
+    ];
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        Node {
+            row_gap: Val::Px(10.0),
+            ..panel_node(Val::Percent(10.0), Val::Percent(25.0))
+        },
+        MenuPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new("Choose a game"),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        if let Some(saved) = &saved {
+            spawn_colored_button(
+                parent,
+                &text_font,
+                format!("Continue {}", saved.variant.name()),
+                Color::srgb(0.3, 0.5, 0.3),
+                ContinueButton,
+            );
+        }
+        if daily_saved || !daily::started_today() {
+            spawn_colored_button(
+                parent,
+                &text_font,
+                format!("Daily deal {today}"),
+                Color::srgb(0.5, 0.4, 0.1),
+                DailyButton,
+            );
+        }
+        if let Some(scenario) = scenario {
+            spawn_colored_button(
+                parent,
+                &text_font,
+                format!("{} scenario", scenario.variant.name()),
+                Color::srgb(0.5, 0.3, 0.3),
+                ScenarioButton,
+            );
+        }
+        for (label, game) in replays.into_iter().flatten() {
+            spawn_colored_button(
+                parent,
+                &text_font,
+                label,
+                Color::srgb(0.3, 0.3, 0.5),
+                ReplayButton(game),
+            );
+        }
+        // The variants in a grid, for the menu to fit in the window.
+        parent
+            .spawn(Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::flex(VARIANT_COLUMNS, 1.0),
+                row_gap: Val::Px(10.0),
+                column_gap: Val::Px(10.0),
+                ..default()
+            })
+            .with_children(|parent| {
+                for variant in Variant::iter() {
+                    spawn_button(parent, &text_font, variant.name(), MenuButton(variant));
+                }
+            });
+        spawn_colored_button(
+            parent,
+            &text_font,
+            "Statistics",
+            Color::srgb(0.3, 0.3, 0.3),
+            StatsButton,
+        );
+    });
+}
+
+fn handle_menu_buttons(
+    interaction_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    panel_query: Query<Entity, With<MenuPanel>>,
+    mut game: ResMut<GameState>,
+    rules: Res<RulesConfig>,
+    show_rules_id: Res<ShowRulesId>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(button.0);
+            for entity in panel_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            // Variants with house rules start from the rules screen.
+            if button.0.has_house_rules() {
+                commands.run_system(show_rules_id.0);
+            } else {
+                *game = GameState::new(button.0, *rules);
+                commands.set_state(AppState::InGame);
+            }
+        }
+    }
+}
+
+fn handle_continue_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
+    mut game: ResMut<GameState>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(saved) = save::load() else {
+            continue;
+        };
+        commands.insert_resource(saved.variant);
+        commands.insert_resource(saved.rules);
+        *game = saved;
+        commands.set_state(AppState::InGame);
+    }
+}
+
+fn handle_daily_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<DailyButton>)>,
+    mut game: ResMut<GameState>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        *game = match save::load() {
+            Some(saved) if saved.daily == Some(daily::today()) => saved,
+            _ => daily::new_game(),
+        };
+        commands.insert_resource(game.variant);
+        commands.insert_resource(game.rules);
+        commands.set_state(AppState::InGame);
+    }
+}
+
+fn handle_scenario_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ScenarioButton>)>,
+    mut game: ResMut<GameState>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(scenario) = scenario::load() else {
+            continue;
+        };
+        commands.insert_resource(scenario.variant);
+        commands.insert_resource(scenario.rules);
+        *game = scenario;
+        commands.set_state(AppState::InGame);
+    }
+}
+
+fn handle_replay_button(
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    mut game: ResMut<GameState>,
+    show_replay_controls_id: Res<ShowReplayControlsId>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let replay = match Replay::new(button.0.clone()) {
+            Ok(replay) => replay,
+            Err(e) => {
+                warn!("Game can't be replayed: {:?}", e);
+                continue;
+            }
+        };
+        commands.insert_resource(replay.saved().variant);
+        commands.insert_resource(replay.saved().rules);
+        *game = replay.game();
+        commands.insert_resource(replay);
+        commands.set_state(AppState::InGame);
+        commands.run_system(show_replay_controls_id.0);
+    }
+}
+
+fn handle_stats_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<StatsButton>)>,
+    panel_query: Query<Entity, With<MenuPanel>>,
+    show_stats_id: Res<ShowStatsId>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            for entity in panel_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.run_system(show_stats_id.0);
+        }
+    }
+}
+
+/// Spawns the button leading back to the start menu once the game is over.
+pub(super) fn spawn_back_to_menu_button(commands: &mut Commands, font: &FontHandle) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    spawn_lone_button(
+        commands,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(90.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        &text_font,
+        "Main Menu",
+        (BackToMenuButton, GameOverButton),
+    );
+}
+
+/// Leaves the table for the start menu.
+fn handle_back_to_menu_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackToMenuButton>)>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            commands.set_state(AppState::MainMenu);
+        }
+    }
+}
+
+/// Clears the start menu once a game is chosen.
+fn despawn_menu(query: Query<Entity, With<MenuPanel>>, mut commands: Commands) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,402 @@
+//! Game records written as plain text, to be shared and archived, and read
+//! back to be replayed.
+//!
+//! A record starts with headers saying what was played:
+//!
+//! ```text
+//! [Variant "Tressette"]
+//! [Seed "4022518012"]
+//! [Players "You, Player 1, Player 2, Player 3"]
+//! [Target "31"]
+//! [LastTrickBonus "On"]
+//! [Thirds "Dropped"]
+//! [Accusi "On"]
+//! [Score "31 18"]
+//! ```
+//!
+//! followed by each hand: the cards dealt to each player, then every trick
+//! with the cards in the order they were played. Anything else a player
+//! does, like choosing trump or capturing from the table, is written as the
+//! move it is, either within the trick it was made in or on a line of its
+//! own:
+//!
+//! ```text
+//! Hand 1
+//! Deal You: 1C 3C 7D ...
+//! Deal Player 1: ...
+//! Player 2 declare napoletana S
+//! Trick 1: Player 2 3S, Player 3 5S, You 1S, Player 1 2S
+//! ```
+//!
+//! Only the headers and the moves are read back: the deals follow from the
+//! seed, and are there for whoever reads the record. A game started from a
+//! scenario has its first deal in the headers too, as `Deal`, the hands
+//! separated by slashes, and `Leader`, the seat leading the first trick. The
+//! daily deal has its date, as `Daily`.
+
+use std::fmt::{self, Write};
+
+use shuftlib::trick_taking::PlayerId;
+use strum::IntoEnumIterator;
+
+use super::{Variant, player_label, save::SavedGame};
+use crate::{
+    games::{
+        MoveEffect, RulesConfig, Thirds,
+        deal::Deal,
+        record::{InvalidMove, Move, card_code, parse_card},
+    },
+    storage,
+};
+
+/// Key the record exported last is stored under, and the one read back.
+pub(super) const KEY: &str = "game_record";
+
+/// Words a move starts with, telling it apart from who made it.
+const MOVE_WORDS: [&str; 8] = [
+    "play", "capture", "trump", "declare", "bid", "call", "give", "discard",
+];
+
+fn on_off(on: bool) -> &'static str {
+    if on { "On" } else { "Off" }
+}
+
+/// Reads back what [`on_off`] wrote.
+fn parse_on_off(value: &str) -> Option<bool> {
+    match value {
+        "On" => Some(true),
+        "Off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Writes `saved` down as a record, replaying it to tell tricks and hands
+/// apart.
+pub(super) fn export(saved: &SavedGame) -> Result<String, InvalidMove> {
+    let mut game = saved.new_game();
+    let mut body = String::new();
+    let mut hands = 0;
+    let mut new_hand = true;
+    let mut tricks = 0;
+    let mut trick: Vec<String> = Vec::new();
+    for mv in &saved.moves {
+        if new_hand {
+            hands += 1;
+            tricks = 0;
+            new_hand = false;
+            let _ = writeln!(body, "\nHand {hands}");
+            for seat in 0..game.seats() {
+                let player = PlayerId::try_from(seat).unwrap();
+                let cards: Vec<String> = game.hand(player).iter().map(|c| card_code(*c)).collect();
+                let _ = writeln!(body, "Deal {}: {}", player_label(player), cards.join(" "));
+            }
+        }
+
+        let name = player_label(game.current_player());
+        game.apply(mv)?;
+        match mv {
+            Move::Play(card) if game.table_cards().is_none() => {
+                trick.push(format!("{name} {}", card_code(*card)));
+            }
+            _ if !trick.is_empty() => trick.push(format!("{name} {mv}")),
+            _ => {
+                let _ = writeln!(body, "{name} {mv}");
+            }
+        }
+
+        if !matches!(mv, Move::Play(_) | Move::Capture(..)) {
+            continue;
+        }
+        let trick_over = match game.last_effect() {
+            Some(MoveEffect::TrickCompleted { .. } | MoveEffect::GameOver { .. }) => true,
+            Some(MoveEffect::HandComplete { .. }) => {
+                new_hand = true;
+                true
+            }
+            _ => false,
+        };
+        if trick_over && !trick.is_empty() {
+            tricks += 1;
+            let _ = writeln!(body, "Trick {tricks}: {}", trick.join(", "));
+            trick.clear();
+        }
+    }
+    if !trick.is_empty() {
+        let _ = writeln!(body, "Trick {}: {}", tricks + 1, trick.join(", "));
+    }
+
+    let players: Vec<String> = (0..game.seats())
+        .map(|seat| player_label(PlayerId::try_from(seat).unwrap()))
+        .collect();
+    let score: Vec<String> = game.score().iter().map(u8::to_string).collect();
+    let mut headers = vec![
+        ("Variant", saved.variant.name().to_string()),
+        ("Seed", saved.seed.to_string()),
+        ("Players", players.join(", ")),
+    ];
+    if saved.variant.has_house_rules() {
+        let rules = &saved.rules;
+        headers.extend([
+            ("Target", rules.target.to_string()),
+            ("LastTrickBonus", on_off(rules.last_trick_bonus).to_string()),
+            ("Thirds", format!("{:?}", rules.thirds)),
+            ("Accusi", on_off(rules.accusi).to_string()),
+        ]);
+    }
+    if let Some(deal) = &saved.deal {
+        headers.extend([
+            ("Deal", deal.hand_codes().join(" / ")),
+            ("Leader", deal.leader().as_usize().to_string()),
+        ]);
+    }
+    if let Some(day) = &saved.daily {
+        headers.push(("Daily", day.clone()));
+    }
+    headers.push(("Score", score.join(" ")));
+
+    let mut text = String::new();
+    for (name, value) in headers {
+        let _ = writeln!(text, "[{name} \"{value}\"]");
+    }
+    text.push_str(&body);
+    Ok(text)
+}
+
+/// A record that couldn't be read, with the line at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ParseRecordError(pub(super) String);
+
+impl fmt::Display for ParseRecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid game record: {}", self.0)
+    }
+}
+
+/// Reads a move made by a player, written after their name. Within a trick a
+/// card on its own is a card played.
+fn parse_move(item: &str, in_trick: bool) -> Option<Move> {
+    let words: Vec<&str> = item.split_whitespace().collect();
+    match words.iter().position(|word| MOVE_WORDS.contains(word)) {
+        Some(start) => words[start..].join(" ").parse().ok(),
+        None if in_trick => parse_card(words.last()?).map(Move::Play),
+        None => None,
+    }
+}
+
+/// Reads a record back, as written by [`export`]. Whether its moves can be
+/// made is only known once it's replayed.
+pub(super) fn import(text: &str) -> Result<SavedGame, ParseRecordError> {
+    let mut variant = None;
+    let mut seed = None;
+    let mut rules = RulesConfig::default();
+    let mut deal = None;
+    let mut leader = 0;
+    let mut daily = None;
+    let mut moves = Vec::new();
+    for line in text.lines().map(str::trim) {
+        let error = || ParseRecordError(line.to_string());
+        if line.is_empty() || line.starts_with("Hand ") || line.starts_with("Deal ") {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let (name, value) = header.split_once(' ').ok_or_else(error)?;
+            let value = value.trim().trim_matches('"');
+            match name {
+                "Variant" => {
+                    variant = Some(
+                        Variant::iter()
+                            .find(|v| v.name() == value)
+                            .ok_or_else(error)?,
+                    )
+                }
+                "Seed" => seed = Some(value.parse().map_err(|_| error())?),
+                "Target" => rules.target = value.parse().map_err(|_| error())?,
+                "LastTrickBonus" => {
+                    rules.last_trick_bonus = parse_on_off(value).ok_or_else(error)?
+                }
+                "Thirds" => {
+                    rules.thirds = match value {
+                        "Dropped" => Thirds::Dropped,
+                        "Carried" => Thirds::Carried,
+                        _ => return Err(error()),
+                    }
+                }
+                "Accusi" => rules.accusi = parse_on_off(value).ok_or_else(error)?,
+                "Deal" => deal = Some(value.to_string()),
+                "Leader" => leader = value.parse().map_err(|_| error())?,
+                "Daily" => daily = Some(value.to_string()),
+                // Anything else is for whoever reads the record.
+                _ => {}
+            }
+        } else if let Some(trick) = line.strip_prefix("Trick ") {
+            let (_, items) = trick.split_once(':').ok_or_else(error)?;
+            for item in items.split(',') {
+                moves.push(parse_move(item, true).ok_or_else(error)?);
+            }
+        } else {
+            moves.push(parse_move(line, false).ok_or_else(error)?);
+        }
+    }
+    let deal = deal
+        .map(|hands| {
+            let hands: Vec<&str> = hands.split('/').collect();
+            Deal::from_codes(&hands, leader).map_err(|e| ParseRecordError(e.to_string()))
+        })
+        .transpose()?;
+    Ok(SavedGame {
+        variant: variant.ok_or_else(|| ParseRecordError("no variant".to_string()))?,
+        rules,
+        seed: seed.ok_or_else(|| ParseRecordError("no seed".to_string()))?,
+        deal,
+        daily,
+        moves,
+    })
+}
+
+/// Writes the record of `saved` where the player can find it, and returns
+/// where that is.
+pub(super) fn write_record(saved: &SavedGame) -> Result<String, String> {
+    let text = export(saved).map_err(|e| format!("{:?}", e))?;
+    storage::write_text(KEY, &text).map_err(|e| e.to_string())?;
+    storage::text_location(KEY).map_err(|e| e.to_string())
+}
+
+/// Returns the game in the record left for the game to read, if there's one.
+pub(super) fn read_record() -> Option<SavedGame> {
+    let text = storage::read_text(KEY)?;
+    match import(&text) {
+        Ok(saved) => Some(saved),
+        Err(e) => {
+            bevy::log::warn!("{e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shuftlib::core::Suit;
+
+    use super::*;
+    use crate::games::{CardGame, accusi::Accuso, fixtures::suit_per_player};
+
+    fn saved(variant: Variant, seed: u64) -> SavedGame {
+        SavedGame {
+            variant,
+            rules: RulesConfig::default(),
+            seed,
+            deal: None,
+            daily: None,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Plays the first legal card, capturing what it can when there's a
+    /// table to capture from.
+    fn next_move(game: &dyn CardGame) -> Move {
+        let card = game.legal_cards()[0];
+        match game.capture_options(card).into_iter().next() {
+            Some(capture) if game.table_cards().is_some() => Move::Capture(card, capture),
+            _ => Move::Play(card),
+        }
+    }
+
+    /// Makes `count` more moves in `saved`, each the one [`next_move`] picks.
+    fn play(saved: &mut SavedGame, count: usize) {
+        let mut game = saved
+            .variant
+            .deal_game(saved.rules, saved.seed, saved.deal.as_ref());
+        for mv in &saved.moves {
+            game.apply(mv).unwrap();
+        }
+        for _ in 0..count {
+            let mv = next_move(game.as_ref());
+            game.apply(&mv).unwrap();
+            saved.moves.push(mv);
+        }
+    }
+
+    fn round_trip(saved: &SavedGame) {
+        let text = export(saved).unwrap();
+        assert_eq!(import(&text).as_ref(), Ok(saved), "{text}");
+    }
+
+    #[test]
+    fn tricks_are_written_in_the_order_they_were_played() {
+        let mut game = saved(Variant::Briscola, 20);
+        play(&mut game, 10);
+        let text = export(&game).unwrap();
+        assert!(text.contains("[Variant \"Briscola\"]"), "{text}");
+        assert!(text.contains("Trick 5:"), "{text}");
+        round_trip(&game);
+    }
+
+    #[test]
+    fn scopa_captures() {
+        let mut game = saved(Variant::Scopa, 7);
+        play(&mut game, 30);
+        assert!(game.moves.iter().any(|mv| matches!(mv, Move::Capture(..))));
+        round_trip(&game);
+    }
+
+    #[test]
+    fn scenario_with_declaration_inside_a_trick() {
+        // Each player holds a whole suit, so each holds a napoletana.
+        let mut game = SavedGame {
+            deal: Some(Deal::new(suit_per_player(), PlayerId::PLAYER_0).unwrap()),
+            ..saved(Variant::Tressette, 3)
+        };
+        play(&mut game, 1);
+        game.moves
+            .push(Move::Declare(Accuso::Napoletana(Suit::Diamonds)));
+        play(&mut game, 10);
+        let text = export(&game).unwrap();
+        assert!(text.contains("[Deal \""), "{text}");
+        assert!(
+            text.lines()
+                .any(|line| line.starts_with("Trick 1:") && line.contains("declare")),
+            "{text}"
+        );
+        round_trip(&game);
+    }
+
+    #[test]
+    fn daily_deal() {
+        let mut game = SavedGame {
+            daily: Some("2026-10-18".to_string()),
+            ..saved(Variant::Tressette, 20261018)
+        };
+        play(&mut game, 10);
+        let text = export(&game).unwrap();
+        assert!(text.contains("[Daily \"2026-10-18\"]"), "{text}");
+        round_trip(&game);
+    }
+
+    #[test]
+    fn house_rules_are_kept() {
+        let mut game = SavedGame {
+            rules: RulesConfig {
+                target: 21,
+                last_trick_bonus: false,
+                thirds: Thirds::Carried,
+                accusi: false,
+            },
+            ..saved(Variant::Tressette, 5)
+        };
+        play(&mut game, 8);
+        round_trip(&game);
+    }
+
+    #[test]
+    fn switches_are_on_or_off() {
+        let mut game = saved(Variant::Tressette, 5);
+        play(&mut game, 2);
+        let text = export(&game).unwrap();
+        for (header, value) in [("LastTrickBonus", "On"), ("Accusi", "On")] {
+            let from = format!("[{header} \"{value}\"]");
+            let to = format!("[{header} \"Yes\"]");
+            assert!(text.contains(&from), "{text}");
+            assert_eq!(import(&text.replace(&from, &to)), Err(ParseRecordError(to)));
+        }
+    }
+}
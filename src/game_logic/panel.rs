@@ -0,0 +1,93 @@
+//! Panels shown over the table and the buttons in them, drawn the same way
+//! wherever they're shown.
+
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, PositionType, Val},
+};
+
+/// Background of a panel.
+pub(super) const PANEL_COLOR: Color = Color::srgba(0., 0., 0., 0.7);
+
+/// Background of a button.
+pub(super) const BUTTON_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+/// Layout of most panels: a column of lines, its top left corner at `top`
+/// and `left`.
+pub(super) fn panel_node(top: Val, left: Val) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        top,
+        left,
+        flex_direction: FlexDirection::Column,
+        row_gap: Val::Px(5.0),
+        padding: UiRect::all(Val::Px(10.0)),
+        ..default()
+    }
+}
+
+/// Spawns a panel laid out by `node`, with `bundle` telling what it's for.
+pub(super) fn spawn_panel<'a>(
+    commands: &'a mut Commands,
+    node: Node,
+    bundle: impl Bundle,
+) -> EntityCommands<'a> {
+    commands.spawn((node, BackgroundColor(PANEL_COLOR), bundle))
+}
+
+/// Spawns a button labelled `label`, with `bundle` telling what pressing it
+/// does.
+pub(super) fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    text_font: &TextFont,
+    label: impl Into<String>,
+    bundle: impl Bundle,
+) {
+    spawn_colored_button(parent, text_font, label, BUTTON_COLOR, bundle);
+}
+
+/// Spawns a button like [`spawn_button`] does, in `color` rather than the
+/// colour of every other button.
+pub(super) fn spawn_colored_button(
+    parent: &mut ChildSpawnerCommands,
+    text_font: &TextFont,
+    label: impl Into<String>,
+    color: Color,
+    bundle: impl Bundle,
+) {
+    parent
+        .spawn((
+            Node {
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            Interaction::None,
+            BackgroundColor(color),
+            bundle,
+        ))
+        .with_children(|parent| spawn_label(parent, text_font, label));
+}
+
+/// Spawns a button on its own rather than in a panel, laid out by `node`.
+pub(super) fn spawn_lone_button(
+    commands: &mut Commands,
+    node: Node,
+    text_font: &TextFont,
+    label: impl Into<String>,
+    bundle: impl Bundle,
+) {
+    commands
+        .spawn((
+            node,
+            Interaction::None,
+            BackgroundColor(BUTTON_COLOR),
+            bundle,
+        ))
+        .with_children(|parent| spawn_label(parent, text_font, label));
+}
+
+fn spawn_label(parent: &mut ChildSpawnerCommands, text_font: &TextFont, label: impl Into<String>) {
+    parent.spawn((Text::new(label), text_font.clone(), TextColor(Color::WHITE)));
+}
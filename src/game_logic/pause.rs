@@ -0,0 +1,201 @@
+//! Putting the game at the table on hold with Escape: cards stop moving and
+//! bots stop thinking until it's resumed, and a menu offers to start over,
+//! change settings or go back to the start menu.
+
+use bevy::{
+    prelude::*,
+    text::TextFont,
+    ui::{FocusPolicy, Interaction, Node, PositionType, Val},
+};
+
+use super::{
+    AppState, FontHandle, GameState,
+    deck::{self, DeckStyle},
+    panel::{PANEL_COLOR, spawn_button},
+    redeal,
+    replay::Replay,
+};
+
+pub(super) struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_pause,
+                handle_pause_buttons.run_if(in_state(AppState::Paused)),
+            ),
+        )
+        .add_systems(OnEnter(AppState::Paused), (pause_time, show_pause_menu))
+        .add_systems(OnExit(AppState::Paused), (resume_time, despawn_pause_menu));
+    }
+}
+
+/// Covers the whole table, for the cards under it not to be picked while the
+/// game is on hold.
+#[derive(Component)]
+struct PauseOverlay;
+
+/// Settings that can be changed in the middle of a game, shown on demand.
+#[derive(Component)]
+struct SettingsPanel;
+
+/// What pressing a button of the pause menu does.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum PauseButton {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+impl PauseButton {
+    fn label(self) -> &'static str {
+        match self {
+            PauseButton::Resume => "Resume",
+            PauseButton::Restart => "Restart",
+            PauseButton::Settings => "Settings",
+            PauseButton::Quit => "Quit to menu",
+        }
+    }
+}
+
+/// Puts the game on hold, or back in play, when Escape is pressed.
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut commands: Commands,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    match state.get() {
+        AppState::InGame => commands.set_state(AppState::Paused),
+        AppState::Paused => commands.set_state(AppState::InGame),
+        _ => {}
+    }
+}
+
+/// Stops the clock that animations and bots go by.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Shows the pause menu over the table. Games can be started over from the
+/// same deal, except in a replay, and in the daily deal where only the first
+/// try counts.
+fn show_pause_menu(
+    font: Res<FontHandle>,
+    game: Res<GameState>,
+    replay: Option<Res<Replay>>,
+    deck_style: Res<DeckStyle>,
+    mut commands: Commands,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    let buttons: Vec<PauseButton> = [
+        PauseButton::Resume,
+        PauseButton::Restart,
+        PauseButton::Settings,
+        PauseButton::Quit,
+    ]
+    .into_iter()
+    .filter(|button| *button != PauseButton::Restart || (replay.is_none() && game.daily.is_none()))
+    .collect();
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0., 0., 0., 0.4)),
+            GlobalZIndex(1),
+            FocusPolicy::Block,
+            PauseOverlay,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(10.0),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    BackgroundColor(PANEL_COLOR),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Paused"),
+                        text_font.clone(),
+                        TextColor(Color::WHITE),
+                    ));
+                    for button in buttons {
+                        spawn_button(parent, &text_font, button.label(), button);
+                        if button == PauseButton::Settings {
+                            parent
+                                .spawn((
+                                    Node {
+                                        display: Display::None,
+                                        flex_direction: FlexDirection::Column,
+                                        padding: UiRect::left(Val::Px(20.0)),
+                                        ..default()
+                                    },
+                                    SettingsPanel,
+                                ))
+                                .with_children(|parent| {
+                                    deck::spawn_deck_option(parent, &font, *deck_style);
+                                });
+                        }
+                    }
+                });
+        });
+}
+
+fn handle_pause_buttons(
+    interaction_query: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    mut settings_query: Query<&mut Node, With<SettingsPanel>>,
+    mut game: ResMut<GameState>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseButton::Resume => commands.set_state(AppState::InGame),
+            PauseButton::Restart => {
+                *game = game.restart();
+                redeal(&mut commands);
+                commands.set_state(AppState::InGame);
+            }
+            PauseButton::Settings => {
+                for mut node in settings_query.iter_mut() {
+                    node.display = match node.display {
+                        Display::None => Display::Flex,
+                        _ => Display::None,
+                    };
+                }
+            }
+            PauseButton::Quit => commands.set_state(AppState::MainMenu),
+        }
+    }
+}
+
+fn despawn_pause_menu(query: Query<Entity, With<PauseOverlay>>, mut commands: Commands) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,136 @@
+//! Moves made besides, or before, playing a card, whatever the game: bids
+//! and exchanges of cards, the choice of trump and accusi. The game tells
+//! what's pending and which moves settle it; the table only shows them and
+//! tells players what was done.
+
+use bevy::{ecs::system::SystemId, prelude::*};
+use shuftlib::trick_taking::PlayerId;
+
+use super::{
+    AnnouncementText, Card, GameState, NextTurnId, SetupGameId, SyncHandsId, ToDespawn,
+    accusi::accuso_message, announce, player_label, suit_name, trump::trump_message,
+};
+use crate::games::{
+    record::{InvalidMove, Move},
+    terziglio::Bid,
+};
+
+pub(super) struct PendingPlugin;
+
+impl Plugin for PendingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BotPendingId>();
+    }
+}
+
+/// How the table moves on after a pending move.
+pub(super) enum Outcome {
+    /// Cards may have changed hands: lay them out again and go on.
+    Continue,
+    /// A new hand was dealt.
+    Redeal,
+}
+
+/// Makes `mv` for the current player and keeps track of it, returning what
+/// to announce to the table.
+pub(super) fn make(game: &mut GameState, mv: Move) -> Result<(String, Outcome), InvalidMove> {
+    let player = game.current_player();
+    let dealer = game.dealer();
+    // Whoever holds the Three asked for, before it changes hands.
+    let holder = match mv {
+        Move::CallThree(three) => (0..game.seats())
+            .filter_map(|seat| PlayerId::try_from(seat).ok())
+            .find(|seat| game.hand(*seat).contains(&three)),
+        _ => None,
+    };
+
+    game.apply(&mv)?;
+    // Only a hand nobody bid in is dealt again before the play.
+    let outcome = if game.dealer() == dealer {
+        Outcome::Continue
+    } else {
+        Outcome::Redeal
+    };
+
+    let who = player_label(player);
+    let message = match (&mv, &outcome) {
+        (Move::Bid(_), Outcome::Redeal) => "Everybody passed, new deal".to_string(),
+        (Move::Bid(Bid::Pass), _) => format!("{who}: pass"),
+        (Move::Bid(Bid::Call), _) => format!("{who}: call"),
+        (Move::CallThree(three), _) => {
+            let asked = format!("{who}: 3 of {}", suit_name(three.suit()));
+            match holder {
+                Some(holder) => format!("{asked}, from {}", player_label(holder)),
+                None => format!("{asked}, in the monte"),
+            }
+        }
+        (Move::GiveBack(_), _) => format!("{who}: takes the monte"),
+        (Move::Discard(_), _) => format!("{who}: plays alone"),
+        (Move::Trump { suit, maraffa }, _) => trump_message(player, *suit, *maraffa),
+        (Move::Declare(accuso), _) => accuso_message(player, *accuso),
+        (Move::Play(_) | Move::Capture(..), _) => String::new(),
+    };
+    game.record(mv);
+    Ok((message, outcome))
+}
+
+/// Lays the table out again after a pending move and hands the turn over.
+pub(super) fn move_on(
+    commands: &mut Commands,
+    outcome: Outcome,
+    card_query: &Query<Entity, With<Card>>,
+    setup_game_id: &SetupGameId,
+    sync_hands_id: &SyncHandsId,
+    next_turn_id: &NextTurnId,
+) {
+    match outcome {
+        Outcome::Continue => {
+            commands.run_system(sync_hands_id.0);
+            commands.run_system(next_turn_id.0);
+        }
+        Outcome::Redeal => {
+            for entity in card_query.iter() {
+                commands.entity(entity).insert(ToDespawn);
+            }
+            commands.run_system(setup_game_id.0);
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct BotPendingId(pub(super) SystemId);
+impl FromWorld for BotPendingId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(bot_pending);
+        BotPendingId(id)
+    }
+}
+
+/// One shot system called when a bot has something to do before the play.
+fn bot_pending(
+    mut game: ResMut<GameState>,
+    mut commands: Commands,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    card_query: Query<Entity, With<Card>>,
+    setup_game_id: Res<SetupGameId>,
+    sync_hands_id: Res<SyncHandsId>,
+    next_turn_id: Res<NextTurnId>,
+) {
+    let Some(mv) = game.bot_move() else {
+        return;
+    };
+    match make(&mut game, mv) {
+        Ok((message, outcome)) => {
+            announce(&mut announcement_query, message);
+            move_on(
+                &mut commands,
+                outcome,
+                &card_query,
+                &setup_game_id,
+                &sync_hands_id,
+                &next_turn_id,
+            );
+        }
+        Err(e) => warn!("AI invalid move: {:?}", e),
+    }
+}
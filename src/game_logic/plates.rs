@@ -0,0 +1,179 @@
+//! Name plates telling who sits where: each one in the colour of its side of
+//! the score, with a marker next to the dealer's and the plate of the player
+//! whose turn it is pulsing.
+
+use bevy::{prelude::*, text::TextFont};
+use shuftlib::{tressette::Status, trick_taking::PlayerId};
+
+use super::{
+    CARD_SIZE, FontHandle, GameState, TableSide, player_label, player_position, table_side,
+};
+use crate::camera::CANVAS_SIZE;
+
+/// Size of a name plate.
+const PLATE_SIZE: Vec2 = Vec2::new(64., 14.);
+
+/// Distance from the middle of a hand of ten cards to the far end of its
+/// last card.
+const HAND_HALF_LENGTH: f32 = 9. * CARD_SIZE.x * 0.25 + CARD_SIZE.x * 0.5;
+
+/// Gap between the end of a hand and the plate of its player.
+const PLATE_MARGIN: f32 = 10.;
+
+/// Colours of the sides of the score, in order.
+const SIDE_COLORS: [Color; 4] = [
+    Color::srgb(0.2, 0.4, 0.8),
+    Color::srgb(0.8, 0.25, 0.2),
+    Color::srgb(0.2, 0.6, 0.3),
+    Color::srgb(0.7, 0.55, 0.1),
+];
+
+/// How many times a second the plate of the current player pulses.
+const PULSE_SPEED: f32 = 1.5;
+
+pub(super) struct PlatesPlugin;
+
+impl Plugin for PlatesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                move_dealer_marker.run_if(resource_changed::<GameState>),
+                highlight_current_player.run_if(any_with_component::<NamePlate>),
+            ),
+        );
+    }
+}
+
+/// Plate with the name of the player in a seat.
+#[derive(Component)]
+pub(super) struct NamePlate(PlayerId);
+
+/// Marker next to the plate of the dealer.
+#[derive(Component)]
+pub(super) struct DealerMarker;
+
+/// Colour of the side `player` scores for: their team in team games,
+/// themselves otherwise.
+fn side_color(game: &GameState, player: PlayerId) -> Color {
+    let sides = game.score().len().max(1);
+    SIDE_COLORS[player.as_usize() % sides % SIDE_COLORS.len()]
+}
+
+/// Direction from the middle of the hand of a player sitting on `side` to
+/// its end on their left.
+fn along_hand(side: TableSide) -> Vec2 {
+    match side {
+        TableSide::Bottom => Vec2::NEG_X,
+        TableSide::Right => Vec2::NEG_Y,
+        TableSide::Top => Vec2::X,
+        TableSide::Left => Vec2::Y,
+    }
+}
+
+/// Half the length of a plate along the hand it's next to.
+fn plate_half_length(side: TableSide) -> f32 {
+    match side {
+        TableSide::Bottom | TableSide::Top => PLATE_SIZE.x * 0.5,
+        TableSide::Right | TableSide::Left => PLATE_SIZE.y * 0.5,
+    }
+}
+
+/// Where the plate of a player sitting on `side` goes: past the end of their
+/// hand on their left.
+fn plate_position(side: TableSide) -> Vec3 {
+    let distance = HAND_HALF_LENGTH + PLATE_MARGIN + plate_half_length(side);
+    player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, side) + (along_hand(side) * distance).extend(20.)
+}
+
+/// Where the dealer marker goes when `player` deals: past their plate, away
+/// from their hand.
+fn dealer_marker_position(game: &GameState, player: PlayerId) -> Vec3 {
+    let side = table_side(game.seats(), player.as_usize());
+    let distance = plate_half_length(side) + PLATE_SIZE.y * 0.5 + 2.;
+    plate_position(side) + (along_hand(side) * distance).extend(0.)
+}
+
+/// Spawns the plate of every seat, and the dealer marker in games where
+/// somebody deals.
+pub(super) fn spawn_name_plates(commands: &mut Commands, font: &FontHandle, game: &GameState) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 10.0,
+        ..default()
+    };
+    for seat in 0..game.seats() {
+        let player = PlayerId::try_from(seat).unwrap();
+        let name = if game.dummy() == Some(player) {
+            format!("{} (dummy)", player_label(player))
+        } else {
+            player_label(player)
+        };
+        commands
+            .spawn((
+                Name::new(format!("Name plate {seat}")),
+                Sprite::from_color(side_color(game, player), PLATE_SIZE),
+                Transform::from_translation(plate_position(table_side(game.seats(), seat))),
+                NamePlate(player),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text2d::new(name),
+                    text_font.clone(),
+                    TextColor(Color::WHITE),
+                    Transform::from_xyz(0., 0., 1.),
+                ));
+            });
+    }
+    if let Some(dealer) = game.dealer() {
+        commands
+            .spawn((
+                Name::new("Dealer marker"),
+                Sprite::from_color(Color::WHITE, Vec2::splat(PLATE_SIZE.y)),
+                Transform::from_translation(dealer_marker_position(game, dealer)),
+                DealerMarker,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text2d::new("D"),
+                    text_font,
+                    TextColor(Color::BLACK),
+                    Transform::from_xyz(0., 0., 1.),
+                ));
+            });
+    }
+}
+
+/// Moves the dealer marker along as the deal passes from hand to hand.
+fn move_dealer_marker(game: Res<GameState>, mut query: Query<&mut Transform, With<DealerMarker>>) {
+    let Some(dealer) = game.dealer() else {
+        return;
+    };
+    for mut transform in query.iter_mut() {
+        transform.translation = dealer_marker_position(&game, dealer);
+    }
+}
+
+/// Makes the plate of the player whose turn it is pulse, and brightens it,
+/// until the game is over.
+fn highlight_current_player(
+    time: Res<Time>,
+    game: Res<GameState>,
+    mut query: Query<(&NamePlate, &mut Transform, &mut Sprite)>,
+) {
+    let current = match game.status() {
+        Status::Ongoing => Some(game.current_player()),
+        Status::Finished { .. } => None,
+    };
+    let pulse = (time.elapsed_secs() * PULSE_SPEED * std::f32::consts::TAU).sin() * 0.5 + 0.5;
+    for (plate, mut transform, mut sprite) in query.iter_mut() {
+        let color = side_color(&game, plate.0);
+        if Some(plate.0) == current {
+            transform.scale = Vec3::splat(1. + 0.15 * pulse);
+            sprite.color = color.mix(&Color::WHITE, 0.4 * pulse);
+        } else {
+            transform.scale = Vec3::ONE;
+            sprite.color = color;
+        }
+    }
+}
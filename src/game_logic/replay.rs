@@ -0,0 +1,439 @@
+//! Replay of the last finished game, move by move and with every hand face
+//! up, to look back at how each hand was played.
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, PositionType, Val},
+};
+use shuftlib::trick_taking::PlayerId;
+
+use super::{
+    AnnouncementText, AppState, CARD_SPEED, Card, CardInPlay, FontHandle, GameState, MovingTo,
+    ScoreText, SetupGameId, TRICK_POSITIONS, ToDespawn, announce, card_name, notation,
+    panel::{spawn_button, spawn_panel},
+    player_label, player_position,
+    save::SavedGame,
+    score_text, table_side,
+};
+use crate::{
+    camera::CANVAS_SIZE,
+    games::{
+        MoveEffect,
+        record::{InvalidMove, Move},
+    },
+};
+
+pub(super) struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                step_replay.run_if(not(in_state(AppState::Paused))),
+                handle_replay_export,
+                handle_replay_exit,
+                update_replay_text.run_if(resource_changed::<Replay>),
+            )
+                .run_if(resource_exists::<Replay>),
+        )
+        .init_resource::<ShowReplayControlsId>()
+        .init_resource::<ReplayArrivedId>()
+        .init_resource::<ReplayLayoutId>();
+    }
+}
+
+/// The game being replayed, and how far into it the table is.
+#[derive(Resource)]
+pub(super) struct Replay {
+    saved: SavedGame,
+    /// Number of moves made on the table.
+    shown: usize,
+    /// Player making each move.
+    players: Vec<PlayerId>,
+    /// Moves each trick starts from.
+    tricks: Vec<usize>,
+    /// Moves each hand starts from.
+    hands: Vec<usize>,
+}
+
+impl Replay {
+    /// Goes through `saved` once, to know who made each move and where each
+    /// trick and hand starts.
+    pub(super) fn new(saved: SavedGame) -> Result<Self, InvalidMove> {
+        let mut game = saved.new_game();
+        let mut players = Vec::with_capacity(saved.moves.len());
+        let mut tricks = vec![0];
+        let mut hands = vec![0];
+        for (index, mv) in saved.moves.iter().enumerate() {
+            players.push(game.current_player());
+            game.apply(mv)?;
+            if !matches!(mv, Move::Play(_) | Move::Capture(..)) {
+                continue;
+            }
+            match game.last_effect() {
+                Some(MoveEffect::TrickCompleted { .. }) => tricks.push(index + 1),
+                Some(MoveEffect::HandComplete { .. }) => {
+                    tricks.push(index + 1);
+                    hands.push(index + 1);
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            saved,
+            shown: 0,
+            players,
+            tricks,
+            hands,
+        })
+    }
+
+    /// The game as it was once the moves shown were made.
+    pub(super) fn game(&self) -> GameState {
+        let saved = SavedGame {
+            moves: self.saved.moves[..self.shown].to_vec(),
+            ..self.saved.clone()
+        };
+        GameState::resume(&saved).expect("the moves were replayed when the replay was created")
+    }
+
+    pub(super) fn saved(&self) -> &SavedGame {
+        &self.saved
+    }
+
+    /// What the last move shown was, and who made it.
+    fn last_move(&self) -> Option<String> {
+        let index = self.shown.checked_sub(1)?;
+        let what = match &self.saved.moves[index] {
+            Move::Play(card) => card_name(*card),
+            Move::Capture(card, capture) if !capture.is_empty() => {
+                let captured: Vec<String> = capture.iter().map(|c| card_name(*c)).collect();
+                format!("{} taking {}", card_name(*card), captured.join(", "))
+            }
+            Move::Capture(card, _) => card_name(*card),
+            mv => mv.to_string(),
+        };
+        Some(format!("{}: {what}", player_label(self.players[index])))
+    }
+}
+
+#[derive(Component)]
+pub(super) struct ReplayPanel;
+
+/// Shows how far into the game the table is.
+#[derive(Component)]
+struct ReplayText;
+
+/// Moves the replay when pressed.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum ReplayButton {
+    PreviousHand,
+    PreviousTrick,
+    PreviousMove,
+    NextMove,
+    NextTrick,
+    NextHand,
+    Export,
+    Exit,
+}
+
+impl ReplayButton {
+    const ALL: [ReplayButton; 8] = [
+        ReplayButton::PreviousHand,
+        ReplayButton::PreviousTrick,
+        ReplayButton::PreviousMove,
+        ReplayButton::NextMove,
+        ReplayButton::NextTrick,
+        ReplayButton::NextHand,
+        ReplayButton::Export,
+        ReplayButton::Exit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ReplayButton::PreviousHand => "<<< Hand",
+            ReplayButton::PreviousTrick => "<< Trick",
+            ReplayButton::PreviousMove => "< Move",
+            ReplayButton::NextMove => "Move >",
+            ReplayButton::NextTrick => "Trick >>",
+            ReplayButton::NextHand => "Hand >>>",
+            ReplayButton::Export => "Export",
+            ReplayButton::Exit => "Exit",
+        }
+    }
+
+    /// Number of moves to show once pressed, if the replay can go that way.
+    fn target(self, replay: &Replay) -> Option<usize> {
+        let shown = replay.shown;
+        let end = replay.saved.moves.len();
+        let previous = |starts: &[usize]| starts.iter().rev().find(|s| **s < shown).copied();
+        let next = |starts: &[usize]| {
+            starts
+                .iter()
+                .find(|s| **s > shown)
+                .copied()
+                .or((shown < end).then_some(end))
+        };
+        match self {
+            ReplayButton::PreviousHand => previous(&replay.hands),
+            ReplayButton::PreviousTrick => previous(&replay.tricks),
+            ReplayButton::PreviousMove => shown.checked_sub(1),
+            ReplayButton::NextMove => (shown < end).then_some(shown + 1),
+            ReplayButton::NextTrick => next(&replay.tricks),
+            ReplayButton::NextHand => next(&replay.hands),
+            ReplayButton::Export | ReplayButton::Exit => None,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct ShowReplayControlsId(pub(super) SystemId);
+impl FromWorld for ShowReplayControlsId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(show_replay_controls);
+        ShowReplayControlsId(id)
+    }
+}
+
+/// One shot system that shows the buttons moving the replay along.
+fn show_replay_controls(font: Res<FontHandle>, mut commands: Commands) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 12.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(70.0),
+            left: Val::Percent(20.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(5.0),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        ReplayPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(""),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+            ReplayText,
+        ));
+        parent
+            .spawn(Node {
+                column_gap: Val::Px(5.0),
+                ..default()
+            })
+            .with_children(|parent| {
+                for button in ReplayButton::ALL {
+                    spawn_button(parent, &text_font, button.label(), button);
+                }
+            });
+    });
+}
+
+/// Moves the replay along when a button is pressed, or from the keyboard:
+/// left and right a move at a time, up and down a trick, page up and page
+/// down a hand.
+///
+/// A card played moves to the trick, anything else lays the table out again.
+fn step_replay(
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut replay: ResMut<Replay>,
+    mut game: ResMut<GameState>,
+    moving_query: Query<(), (With<Card>, With<MovingTo>)>,
+    hand_cards: Query<(Entity, &Card), With<ChildOf>>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    replay_arrived_id: Res<ReplayArrivedId>,
+    replay_layout_id: Res<ReplayLayoutId>,
+    mut commands: Commands,
+) {
+    let bindings = [
+        (KeyCode::PageUp, ReplayButton::PreviousHand),
+        (KeyCode::ArrowUp, ReplayButton::PreviousTrick),
+        (KeyCode::ArrowLeft, ReplayButton::PreviousMove),
+        (KeyCode::ArrowRight, ReplayButton::NextMove),
+        (KeyCode::ArrowDown, ReplayButton::NextTrick),
+        (KeyCode::PageDown, ReplayButton::NextHand),
+    ];
+    let pressed = interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button)
+        .chain(
+            bindings
+                .into_iter()
+                .filter(|(key, _)| keys.just_pressed(*key))
+                .map(|(_, button)| button),
+        )
+        .next();
+    let Some(target) = pressed.and_then(|button| button.target(&replay)) else {
+        return;
+    };
+    // Let the last card played land first.
+    if !moving_query.is_empty() {
+        return;
+    }
+
+    if target == replay.shown + 1 {
+        let mv = replay.saved.moves[replay.shown].clone();
+        let player = replay.players[replay.shown];
+        if let Err(e) = game.replay(mv.clone()) {
+            warn!("Invalid replayed move: {:?}", e);
+            return;
+        }
+        replay.shown = target;
+        announce(
+            &mut announcement_query,
+            replay.last_move().unwrap_or_default(),
+        );
+
+        let played = match mv {
+            Move::Play(card) | Move::Capture(card, _) => Some(card),
+            _ => None,
+        };
+        if let Some((entity, _)) =
+            played.and_then(|card| hand_cards.iter().find(|(_, c)| c.0 == card))
+        {
+            let (x, y) = TRICK_POSITIONS[table_side(game.seats(), player.as_usize()) as usize];
+            commands.entity(entity).remove_parent_in_place().insert((
+                CardInPlay,
+                MovingTo {
+                    target: Vec3::new(x, y, 10.0),
+                    speed: CARD_SPEED,
+                    on_arrival: Some(replay_arrived_id.0),
+                },
+            ));
+            return;
+        }
+    } else {
+        replay.shown = target;
+        *game = replay.game();
+        announce(
+            &mut announcement_query,
+            replay.last_move().unwrap_or_default(),
+        );
+    }
+    commands.run_system(replay_layout_id.0);
+}
+
+#[derive(Resource)]
+struct ReplayArrivedId(SystemId);
+impl FromWorld for ReplayArrivedId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(replay_arrived);
+        ReplayArrivedId(id)
+    }
+}
+
+/// Called when a card replayed reaches the trick. A completed trick goes to
+/// whoever took it before the table is laid out again.
+fn replay_arrived(
+    game: Res<GameState>,
+    in_play_query: Query<Entity, (With<CardInPlay>, Without<MovingTo>)>,
+    replay_layout_id: Res<ReplayLayoutId>,
+    mut commands: Commands,
+) {
+    // Captures are laid out with the rest of the table.
+    if game.table_cards().is_some() {
+        commands.run_system(replay_layout_id.0);
+        return;
+    }
+    let winner = match game.last_effect() {
+        Some(MoveEffect::TrickCompleted { winner }) => winner,
+        Some(MoveEffect::HandComplete { trick_winner } | MoveEffect::GameOver { trick_winner }) => {
+            trick_winner
+        }
+        _ => return,
+    };
+    for entity in in_play_query.iter() {
+        commands.entity(entity).insert(MovingTo {
+            target: player_position(
+                CANVAS_SIZE.x,
+                CANVAS_SIZE.y,
+                table_side(game.seats(), winner.as_usize()),
+            ),
+            speed: CARD_SPEED,
+            on_arrival: Some(replay_layout_id.0),
+        });
+    }
+}
+
+#[derive(Resource)]
+struct ReplayLayoutId(SystemId);
+impl FromWorld for ReplayLayoutId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(replay_layout);
+        ReplayLayoutId(id)
+    }
+}
+
+/// One shot system laying the table out again as the game was after the
+/// moves shown, once every card is done moving.
+fn replay_layout(
+    game: Res<GameState>,
+    moving_query: Query<(), (With<Card>, With<MovingTo>)>,
+    card_query: Query<Entity, With<Card>>,
+    mut score_text_query: Query<&mut Text, With<ScoreText>>,
+    setup_game_id: Res<SetupGameId>,
+    mut commands: Commands,
+) {
+    if !moving_query.is_empty() {
+        return;
+    }
+    for entity in card_query.iter() {
+        commands.entity(entity).insert(ToDespawn);
+    }
+    if let Ok(mut text) = score_text_query.single_mut() {
+        *text = Text::new(score_text("Score", &game.score()));
+    }
+    commands.run_system(setup_game_id.0);
+}
+
+/// Writes the game being replayed down as a record, for it to be shared.
+fn handle_replay_export(
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    replay: Res<Replay>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed || *button != ReplayButton::Export {
+            continue;
+        }
+        let message = match notation::write_record(&replay.saved) {
+            Ok(location) => format!("Game record written to {location}"),
+            Err(e) => {
+                warn!("Game record can't be written: {e}");
+                "Game record can't be written".to_string()
+            }
+        };
+        announce(&mut announcement_query, message);
+    }
+}
+
+/// Leaves the replay for the start menu.
+fn handle_replay_exit(
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed && *button == ReplayButton::Exit {
+            commands.set_state(AppState::MainMenu);
+        }
+    }
+}
+
+/// Keeps the position in the game in line with the replay.
+fn update_replay_text(replay: Res<Replay>, mut query: Query<&mut Text, With<ReplayText>>) {
+    for mut text in query.iter_mut() {
+        **text = format!("Move {} of {}", replay.shown, replay.saved.moves.len());
+    }
+}
@@ -0,0 +1,157 @@
+//! Screen choosing the house rules, shown before starting the variants that
+//! have some.
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, Val},
+};
+
+use super::{
+    AppState, FontHandle, GameState, Variant,
+    panel::{panel_node, spawn_button, spawn_panel},
+};
+use crate::games::{RulesConfig, Thirds};
+
+pub(super) struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_rules_buttons)
+            .init_resource::<ShowRulesId>();
+    }
+}
+
+#[derive(Component)]
+struct RulesPanel;
+
+/// What pressing a button of the rules screen does.
+#[derive(Component, Clone, Copy)]
+enum RulesButton {
+    Target,
+    LastTrickBonus,
+    Thirds,
+    Accusi,
+    Start,
+}
+
+impl RulesButton {
+    const OPTIONS: [RulesButton; 4] = [
+        RulesButton::Target,
+        RulesButton::LastTrickBonus,
+        RulesButton::Thirds,
+        RulesButton::Accusi,
+    ];
+
+    /// Label of the button, showing the current choice.
+    fn label(self, rules: &RulesConfig) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        match self {
+            RulesButton::Target => format!("Play to: {}", rules.target),
+            RulesButton::LastTrickBonus => {
+                format!("Last trick bonus: {}", on_off(rules.last_trick_bonus))
+            }
+            RulesButton::Thirds => match rules.thirds {
+                Thirds::Dropped => "Leftover thirds: Dropped".to_string(),
+                Thirds::Carried => "Leftover thirds: Carried".to_string(),
+            },
+            RulesButton::Accusi => format!("Accusi: {}", on_off(rules.accusi)),
+            RulesButton::Start => "Start".to_string(),
+        }
+    }
+
+    /// Moves the option on to its next choice.
+    fn toggle(self, rules: &mut RulesConfig) {
+        match self {
+            RulesButton::Target => {
+                let index = RulesConfig::TARGETS
+                    .iter()
+                    .position(|target| *target == rules.target)
+                    .map_or(0, |index| (index + 1) % RulesConfig::TARGETS.len());
+                rules.target = RulesConfig::TARGETS[index];
+            }
+            RulesButton::LastTrickBonus => rules.last_trick_bonus = !rules.last_trick_bonus,
+            RulesButton::Thirds => {
+                rules.thirds = match rules.thirds {
+                    Thirds::Dropped => Thirds::Carried,
+                    Thirds::Carried => Thirds::Dropped,
+                }
+            }
+            RulesButton::Accusi => rules.accusi = !rules.accusi,
+            RulesButton::Start => {}
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct ShowRulesId(pub(super) SystemId);
+impl FromWorld for ShowRulesId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(show_rules);
+        ShowRulesId(id)
+    }
+}
+
+/// One shot system that shows the house rules of the chosen variant, for the
+/// player to change before starting.
+fn show_rules(
+    font: Res<FontHandle>,
+    variant: Res<Variant>,
+    rules: Res<RulesConfig>,
+    mut commands: Commands,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        Node {
+            row_gap: Val::Px(10.0),
+            ..panel_node(Val::Percent(30.0), Val::Percent(35.0))
+        },
+        RulesPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(format!("{} rules", variant.name())),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        for button in RulesButton::OPTIONS.into_iter().chain([RulesButton::Start]) {
+            spawn_button(parent, &text_font, button.label(&rules), button);
+        }
+    });
+}
+
+fn handle_rules_buttons(
+    interaction_query: Query<(&Interaction, &RulesButton, &Children), Changed<Interaction>>,
+    mut text_query: Query<&mut Text>,
+    panel_query: Query<Entity, With<RulesPanel>>,
+    mut rules: ResMut<RulesConfig>,
+    mut game: ResMut<GameState>,
+    variant: Res<Variant>,
+    mut commands: Commands,
+) {
+    for (interaction, button, children) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let RulesButton::Start = button {
+            *game = GameState::new(*variant, *rules);
+            for entity in panel_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.set_state(AppState::InGame);
+            continue;
+        }
+        button.toggle(&mut rules);
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                **text = button.label(&rules);
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Saving the game in progress after every move, for it to be continued from
+//! the start menu after the window was closed, and the last finished game,
+//! for it to be replayed.
+//!
+//! Only what it takes to deal the game again is saved: the variant, its house
+//! rules, the seed of the deck, the deal it started from if it wasn't
+//! shuffled and the moves made since.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use shuftlib::tressette::Status;
+
+use super::{GameState, Variant, replay::Replay};
+use crate::{
+    games::{CardGame, RulesConfig, deal::Deal, record::Move},
+    storage,
+};
+
+/// Key the game in progress is stored under.
+const KEY: &str = "saved_game";
+
+/// Key the last finished game is stored under.
+const LAST_GAME_KEY: &str = "last_game";
+
+pub(super) struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, autosave.run_if(resource_changed::<GameState>));
+    }
+}
+
+/// A game in progress, as stored between sessions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct SavedGame {
+    pub(super) variant: Variant,
+    pub(super) rules: RulesConfig,
+    pub(super) seed: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) deal: Option<Deal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) daily: Option<String>,
+    pub(super) moves: Vec<Move>,
+}
+
+impl SavedGame {
+    fn of(game: &GameState) -> Self {
+        Self {
+            variant: game.variant,
+            rules: game.rules,
+            seed: game.seed,
+            deal: game.deal.clone(),
+            daily: game.daily.clone(),
+            moves: game.moves.clone(),
+        }
+    }
+
+    /// Deals the game again, before any move is made.
+    pub(super) fn new_game(&self) -> Box<dyn CardGame> {
+        self.variant
+            .deal_game(self.rules, self.seed, self.deal.as_ref())
+    }
+}
+
+fn read(key: &str) -> Option<SavedGame> {
+    match ron::from_str(&storage::read(key)?) {
+        Ok(saved) => Some(saved),
+        Err(e) => {
+            warn!("Unreadable saved game: {e}");
+            None
+        }
+    }
+}
+
+/// Returns the game saved last time, dealt again and replayed up to where it
+/// was left, if there's one.
+pub(super) fn load() -> Option<GameState> {
+    let saved = read(KEY)?;
+    match GameState::resume(&saved) {
+        Ok(game) => Some(game),
+        Err(e) => {
+            warn!("Saved game can't be replayed: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Returns the last finished game, to be replayed.
+pub(super) fn load_last_game() -> Option<SavedGame> {
+    read(LAST_GAME_KEY)
+}
+
+/// What was saved last: which game it is and how many moves had been made.
+#[derive(Default)]
+struct Saved {
+    game: Option<u64>,
+    moves: usize,
+}
+
+/// Saves the game whenever a move is made. Once it's over, it's kept as the
+/// last game instead.
+fn autosave(game: Res<GameState>, replay: Option<Res<Replay>>, mut saved: Local<Saved>) {
+    // A game being replayed is saved already.
+    let saved_before = saved.game == Some(game.id);
+    if replay.is_some() || (saved_before && saved.moves == game.moves.len()) {
+        return;
+    }
+    // A game nobody moved in yet is as good as a new one, so there's nothing
+    // to save. If its moves were all taken back, what was saved goes too.
+    if game.moves.is_empty() && !saved_before {
+        return;
+    }
+    *saved = Saved {
+        game: Some(game.id),
+        moves: game.moves.len(),
+    };
+    if game.moves.is_empty() {
+        if let Err(e) = storage::remove(KEY) {
+            warn!("Saved game can't be removed: {e}");
+        }
+        return;
+    }
+
+    let contents = match ron::to_string(&SavedGame::of(&game)) {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn!("Game can't be saved: {e}");
+            return;
+        }
+    };
+    let result = if matches!(game.status(), Status::Finished { .. }) {
+        storage::write(LAST_GAME_KEY, &contents).and_then(|()| storage::remove(KEY))
+    } else {
+        storage::write(KEY, &contents)
+    };
+    if let Err(e) = result {
+        warn!("Game can't be saved: {e}");
+    }
+}
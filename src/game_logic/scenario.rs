@@ -0,0 +1,100 @@
+//! Games started from a scenario file rather than a shuffled deck: the four
+//! hands, who leads and the moves already made, to teach a position or to
+//! reproduce a bug.
+//!
+//! The scenario is read from `scenario.ron` or `scenario.json` in the data
+//! directory, `shuftle.scenario` in the browser's local storage:
+//!
+//! ```text
+//! (
+//!     variant: Tressette,
+//!     deal: (
+//!         hands: [
+//!             "1C 2C 3C 4C 5C 6C 7C 8C 9C 10C",
+//!             "1D 2D 3D 4D 5D 6D 7D 8D 9D 10D",
+//!             "1B 2B 3B 4B 5B 6B 7B 8B 9B 10B",
+//!             "1S 2S 3S 4S 5S 6S 7S 8S 9S 10S",
+//!         ],
+//!         leader: 0,
+//!     ),
+//!     moves: ["play 3C"],
+//! )
+//! ```
+//!
+//! Only `deal` is required. The variant defaults to Tressette, the house
+//! rules to the default ones and the hands after the first one are shuffled
+//! from `seed`, or from a random one.
+
+use bevy::log::warn;
+use serde::Deserialize;
+
+use super::{GameState, Variant, save::SavedGame};
+use crate::{
+    games::{RulesConfig, deal::Deal, record::Move},
+    storage,
+};
+
+/// Key the scenario is read from.
+const KEY: &str = "scenario";
+
+#[derive(Debug, Deserialize)]
+struct Scenario {
+    #[serde(default)]
+    variant: Variant,
+    #[serde(default)]
+    rules: RulesConfig,
+    deal: Deal,
+    #[serde(default)]
+    moves: Vec<Move>,
+    seed: Option<u64>,
+}
+
+fn read() -> Result<Option<Scenario>, String> {
+    let Some(contents) = storage::read(KEY).or_else(|| storage::read_json(KEY)) else {
+        return Ok(None);
+    };
+    // JSON is an object, where RON is a struct.
+    if contents.trim_start().starts_with('{') {
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    } else {
+        ron::from_str(&contents)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Returns the game set up as in the scenario file, with its moves made, if
+/// there's one that can be played.
+pub(super) fn load() -> Option<GameState> {
+    let scenario = match read() {
+        Ok(scenario) => scenario?,
+        Err(e) => {
+            warn!("Unreadable scenario: {e}");
+            return None;
+        }
+    };
+    if !scenario.variant.takes_deals() {
+        warn!(
+            "{} can't be played from a scenario",
+            scenario.variant.name()
+        );
+        return None;
+    }
+    let saved = SavedGame {
+        variant: scenario.variant,
+        rules: scenario.rules,
+        seed: scenario.seed.unwrap_or_else(rand::random),
+        deal: Some(scenario.deal),
+        daily: None,
+        moves: scenario.moves,
+    };
+    match GameState::resume(&saved) {
+        Ok(game) => Some(game),
+        Err(e) => {
+            warn!("Scenario can't be played: {:?}", e);
+            None
+        }
+    }
+}
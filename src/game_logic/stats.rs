@@ -0,0 +1,330 @@
+//! Statistics of the games played, kept between sessions, and the screen
+//! showing them from the start menu.
+//!
+//! Hands and games are counted from the POV player's side of the table: their
+//! team in team games, themselves otherwise.
+
+use std::collections::HashMap;
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Val},
+};
+use serde::{Deserialize, Serialize};
+use shuftlib::tressette::Status;
+use strum::IntoEnumIterator;
+
+use super::{
+    FontHandle, GameState, POV_SIDE, Variant, daily,
+    menu::ShowMenuId,
+    panel::{panel_node, spawn_button, spawn_panel},
+    replay::Replay,
+    score_text,
+};
+use crate::{
+    games::{MoveEffect, record::Move},
+    storage,
+};
+
+/// Key the statistics are stored under.
+const KEY: &str = "statistics";
+
+/// Number of daily deals whose results are shown, the latest ones.
+const DAILY_RESULTS_SHOWN: usize = 7;
+
+pub(super) struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                record_stats.run_if(resource_changed::<GameState>),
+                handle_back_button,
+            ),
+        )
+        .init_resource::<ShowStatsId>();
+    }
+}
+
+/// What's been done in the games of a variant.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct VariantStats {
+    /// Games played to the end.
+    played: u32,
+    won: u32,
+    lost: u32,
+    /// Hands played to the end.
+    hands: u32,
+    /// Points made in all those hands.
+    points: u32,
+    /// Most points made in a single hand, or fewest in variants where the
+    /// fewest points win.
+    best_hand: Option<u8>,
+    /// Hands in which the POV side made cappotto.
+    cappotti: u32,
+    /// Hands in which another side made cappotto.
+    cappotti_suffered: u32,
+    /// Games won in a row, up to the last one.
+    streak: u32,
+    best_streak: u32,
+}
+
+impl VariantStats {
+    fn points_per_hand(&self) -> f32 {
+        if self.hands == 0 {
+            0.
+        } else {
+            self.points as f32 / self.hands as f32
+        }
+    }
+}
+
+/// Statistics of every variant played so far.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Statistics {
+    variants: HashMap<Variant, VariantStats>,
+}
+
+impl Statistics {
+    fn load() -> Self {
+        let Some(contents) = storage::read(KEY) else {
+            return Self::default();
+        };
+        ron::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Unreadable statistics: {e}");
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        let result = ron::to_string(self)
+            .map_err(std::io::Error::other)
+            .and_then(|contents| storage::write(KEY, &contents));
+        if let Err(e) = result {
+            warn!("Statistics can't be saved: {e}");
+        }
+    }
+
+    /// Totals over every variant. The best hand is the most points made in
+    /// variants where the most points win.
+    fn total(&self) -> VariantStats {
+        self.variants
+            .iter()
+            .fold(VariantStats::default(), |total, (variant, stats)| {
+                VariantStats {
+                    played: total.played + stats.played,
+                    won: total.won + stats.won,
+                    lost: total.lost + stats.lost,
+                    hands: total.hands + stats.hands,
+                    points: total.points + stats.points,
+                    best_hand: if variant.fewest_points_win() {
+                        total.best_hand
+                    } else {
+                        total.best_hand.max(stats.best_hand)
+                    },
+                    cappotti: total.cappotti + stats.cappotti,
+                    cappotti_suffered: total.cappotti_suffered + stats.cappotti_suffered,
+                    streak: 0,
+                    best_streak: total.best_streak.max(stats.best_streak),
+                }
+            })
+    }
+}
+
+/// Where the game being recorded was up to: which game it is and how many
+/// moves had been made when its last hand ended. Moves taken back and made
+/// again don't count the same hand twice.
+#[derive(Default)]
+struct Recorded {
+    game: Option<u64>,
+    moves: usize,
+}
+
+/// Counts every hand completed and every game finished, as soon as the last
+/// card of it is played.
+fn record_stats(game: Res<GameState>, replay: Option<Res<Replay>>, mut recorded: Local<Recorded>) {
+    if replay.is_some() {
+        return;
+    }
+    // A game just started, or continued from where it was saved: whatever
+    // was played before isn't counted again.
+    if recorded.game != Some(game.id) {
+        *recorded = Recorded {
+            game: Some(game.id),
+            moves: game.moves.len(),
+        };
+        return;
+    }
+    // Only the card ending a hand counts, and only the first time it's played.
+    if game.moves.len() <= recorded.moves
+        || !matches!(game.moves.last(), Some(Move::Play(_) | Move::Capture(..)))
+    {
+        return;
+    }
+    let game_over = match game.last_effect() {
+        Some(MoveEffect::HandComplete { .. }) => false,
+        Some(MoveEffect::GameOver { .. }) => true,
+        _ => return,
+    };
+
+    let (made, suffered) = match game.cappotto() {
+        Some(side) => (side == POV_SIDE, side != POV_SIDE),
+        None => (false, false),
+    };
+
+    let mut stats = Statistics::load();
+    let variant = stats.variants.entry(game.variant).or_default();
+    let points = game
+        .hand_points()
+        .get(POV_SIDE)
+        .copied()
+        .unwrap_or_default();
+    variant.hands += 1;
+    variant.points += u32::from(points);
+    variant.best_hand = Some(match variant.best_hand {
+        Some(best) if game.variant.fewest_points_win() => best.min(points),
+        Some(best) => best.max(points),
+        None => points,
+    });
+    variant.cappotti += u32::from(made);
+    variant.cappotti_suffered += u32::from(suffered);
+    if game_over && let Status::Finished { winner } = game.status() {
+        variant.played += 1;
+        if usize::from(winner) == POV_SIDE {
+            variant.won += 1;
+            variant.streak += 1;
+            variant.best_streak = variant.best_streak.max(variant.streak);
+        } else {
+            variant.lost += 1;
+            variant.streak = 0;
+        }
+    }
+    stats.save();
+
+    *recorded = Recorded {
+        game: Some(game.id),
+        moves: game.moves.len(),
+    };
+}
+
+#[derive(Component)]
+struct StatsPanel;
+
+#[derive(Component)]
+struct BackButton;
+
+#[derive(Resource)]
+pub(super) struct ShowStatsId(pub(super) SystemId);
+impl FromWorld for ShowStatsId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(show_stats);
+        ShowStatsId(id)
+    }
+}
+
+fn stats_line(name: &str, stats: &VariantStats) -> String {
+    format!(
+        "{name}: {} played, {} won, {} lost, {:.1} points per hand, best hand {}, \
+         cappotti {}/{}, best streak {}",
+        stats.played,
+        stats.won,
+        stats.lost,
+        stats.points_per_hand(),
+        stats
+            .best_hand
+            .map_or_else(|| "-".to_string(), |best| best.to_string()),
+        stats.cappotti,
+        stats.cappotti_suffered,
+        stats.best_streak,
+    )
+}
+
+/// One shot system that shows the statistics of every variant played so far,
+/// along with their totals, and the results of the last daily deals.
+fn show_stats(font: Res<FontHandle>, mut commands: Commands) {
+    let stats = Statistics::load();
+    let daily_results = daily::results();
+    let title_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        panel_node(Val::Percent(20.0), Val::Percent(15.0)),
+        StatsPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new("Statistics"),
+            title_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        if stats.variants.is_empty() {
+            parent.spawn((
+                Text::new("No game played yet"),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+        } else {
+            parent.spawn((
+                Text::new(stats_line("All games", &stats.total())),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+            for variant in Variant::iter() {
+                if let Some(variant_stats) = stats.variants.get(&variant) {
+                    parent.spawn((
+                        Text::new(stats_line(variant.name(), variant_stats)),
+                        text_font.clone(),
+                        TextColor(Color::WHITE),
+                    ));
+                }
+            }
+        }
+        if !daily_results.is_empty() {
+            parent.spawn((
+                Text::new("Daily deals"),
+                title_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+        }
+        for (day, result) in daily_results.iter().rev().take(DAILY_RESULTS_SHOWN) {
+            parent.spawn((
+                Text::new(score_text(
+                    &format!("{day}, {}", if result.won { "won" } else { "lost" }),
+                    &result.score,
+                )),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+        }
+        spawn_button(parent, &title_font, "Back", BackButton);
+    });
+}
+
+fn handle_back_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    panel_query: Query<Entity, With<StatsPanel>>,
+    show_menu_id: Res<ShowMenuId>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            for entity in panel_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.run_system(show_menu_id.0);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Summary of the points made in each hand, line by line, shown before the
+//! next hand is dealt.
+
+use bevy::{
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Val},
+};
+
+use super::{
+    AppState, FontHandle, GameState, SetupGameId,
+    panel::{panel_node, spawn_button, spawn_panel},
+    score_text,
+};
+
+pub(super) struct SummaryPlugin;
+
+impl Plugin for SummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_continue_button.run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+#[derive(Component)]
+pub(super) struct SummaryPanel;
+
+#[derive(Component)]
+struct ContinueButton;
+
+/// Shows what each side made in the hand just completed. Unless the game is
+/// over, the next hand is dealt once the player is done reading.
+pub(super) fn show_summary(
+    commands: &mut Commands,
+    font: &FontHandle,
+    game: &GameState,
+    game_over: bool,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_panel(
+        commands,
+        panel_node(Val::Percent(25.0), Val::Percent(35.0)),
+        SummaryPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new("Hand summary"),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        for line in game.hand_summary() {
+            parent.spawn((
+                Text::new(score_text(&line.label, &line.points)),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+        }
+        if !game_over {
+            spawn_button(parent, &text_font, "Continue", ContinueButton);
+        }
+    });
+}
+
+fn handle_continue_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ContinueButton>)>,
+    panel_query: Query<Entity, With<SummaryPanel>>,
+    setup_game_id: Res<SetupGameId>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            for entity in panel_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.run_system(setup_game_id.0);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Cards laid face up on the table, for games where they're captured rather
+//! than played in tricks.
+
+use std::f32::consts::PI;
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Val},
+};
+use shuftlib::tressette::TressetteCard;
+
+use super::{
+    AppState, CARD_SIZE, CARD_SPEED, Card, CardInPlay, EnablePovId, FontHandle, GameState,
+    HandleEffectId, ItalianAssets, MovingTo, Selected, TableSide, card_name,
+    panel::{panel_node, spawn_button, spawn_panel},
+    player_position, selection_offset, send_pov_card_to_trick, table_side,
+};
+use crate::{
+    camera::CANVAS_SIZE,
+    games::{MoveEffect, record::Move},
+};
+
+/// Gap between two cards on the table.
+const TABLE_GAP: f32 = 4.;
+
+/// Distance of the first scopa marker from the centre of its player's seat.
+const SCOPA_MARKER_OFFSET: f32 = 80.;
+
+/// Distance between two scopa markers of the same player.
+const SCOPA_MARKER_SPACING: f32 = 8.;
+
+pub(super) struct TablePlugin;
+
+impl Plugin for TablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (handle_capture_buttons, handle_cancel_capture_button)
+                    .run_if(in_state(AppState::InGame)),
+                update_scopa_markers.run_if(resource_changed::<GameState>),
+            ),
+        )
+        .init_resource::<SettleTableId>();
+    }
+}
+
+/// A card lying face up on the table.
+#[derive(Component)]
+pub(super) struct TableCard;
+
+/// A card turned crosswise next to a player, for each scopa they made.
+#[derive(Component)]
+pub(super) struct ScopaMarker;
+
+#[derive(Component)]
+pub(super) struct CapturePanel;
+
+/// Plays `card` capturing `capture` when pressed.
+#[derive(Component)]
+struct CaptureButton {
+    card_entity: Entity,
+    card: TressetteCard,
+    capture: Vec<TressetteCard>,
+}
+
+/// Puts the card back in the hand without capturing anything.
+#[derive(Component)]
+struct CancelCaptureButton;
+
+/// Position of the card at `index` of a table holding `count` cards: a row
+/// across the centre.
+fn table_slot(index: usize, count: usize) -> Vec3 {
+    let spacing = CARD_SIZE.x + TABLE_GAP;
+    let total_width = count.saturating_sub(1) as f32 * spacing;
+    Vec3::new(index as f32 * spacing - total_width / 2.0, 0., index as f32)
+}
+
+/// Spawns the cards laid on the table at the start of a hand.
+pub(super) fn spawn_table_cards(
+    commands: &mut Commands,
+    game: &GameState,
+    italian_assets: &ItalianAssets,
+) {
+    let Some(table) = game.table_cards() else {
+        return;
+    };
+    for (index, card) in table.iter().enumerate() {
+        commands.spawn((
+            Card(*card),
+            Sprite {
+                custom_size: Some(CARD_SIZE),
+                image: italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone(),
+                ..default()
+            },
+            Transform::from_translation(table_slot(index, table.len())),
+            TableCard,
+        ));
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct SettleTableId(pub(super) SystemId);
+impl FromWorld for SettleTableId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(settle_table);
+        SettleTableId(id)
+    }
+}
+
+/// One shot system run once a played card reaches the table. A card that
+/// captured nothing joins the table, while captured cards are put in play to
+/// be collected with it. Once the hand is over, whatever is left goes too.
+fn settle_table(
+    mut commands: Commands,
+    game: Res<GameState>,
+    cards: Query<(Entity, &Card, Has<TableCard>), Or<(With<TableCard>, With<CardInPlay>)>>,
+) {
+    let Some(table) = game.table_cards() else {
+        return;
+    };
+    let hand_over = matches!(
+        game.last_effect(),
+        Some(MoveEffect::HandComplete { .. } | MoveEffect::GameOver { .. })
+    );
+
+    for (entity, card, on_table) in cards.iter() {
+        match table.iter().position(|c| *c == card.0) {
+            Some(index) if !hand_over => {
+                commands.entity(entity).remove::<CardInPlay>().insert((
+                    TableCard,
+                    MovingTo {
+                        target: table_slot(index, table.len()),
+                        speed: CARD_SPEED,
+                        on_arrival: None,
+                    },
+                ));
+            }
+            _ if on_table => {
+                commands
+                    .entity(entity)
+                    .remove::<TableCard>()
+                    .insert(CardInPlay);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Lets the POV player choose what `card` captures, when there's more than
+/// one way.
+pub(super) fn show_capture_choice(
+    commands: &mut Commands,
+    font: &FontHandle,
+    card_entity: Entity,
+    card: TressetteCard,
+    options: Vec<Vec<TressetteCard>>,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_panel(
+        commands,
+        panel_node(Val::Percent(35.0), Val::Percent(35.0)),
+        CapturePanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new(format!("Capture with the {}", card_name(card))),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        for capture in options {
+            let label: Vec<String> = capture.iter().map(|c| card_name(*c)).collect();
+            spawn_button(
+                parent,
+                &text_font,
+                label.join(" + "),
+                CaptureButton {
+                    card_entity,
+                    card,
+                    capture,
+                },
+            );
+        }
+        spawn_button(parent, &text_font, "Cancel", CancelCaptureButton);
+    });
+}
+
+fn handle_capture_buttons(
+    interaction_query: Query<(&Interaction, &CaptureButton), Changed<Interaction>>,
+    panel_query: Query<Entity, With<CapturePanel>>,
+    mut game: ResMut<GameState>,
+    handle_effect_id: Res<HandleEffectId>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let side = table_side(game.seats(), game.current_player().as_usize());
+        let mv = Move::Capture(button.card, button.capture.clone());
+        match game.apply(&mv) {
+            Ok(()) => {
+                game.record(mv);
+                send_pov_card_to_trick(&mut commands, button.card_entity, side, &handle_effect_id);
+                for entity in panel_query.iter() {
+                    commands.entity(entity).despawn();
+                }
+            }
+            Err(e) => warn!("Invalid capture: {:?}", e),
+        }
+    }
+}
+
+/// Closes the capture choice, lowering the card back into the hand for the
+/// POV player to pick again.
+fn handle_cancel_capture_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<CancelCaptureButton>)>,
+    panel_query: Query<Entity, With<CapturePanel>>,
+    mut selected_query: Query<(Entity, &mut Transform), With<Selected>>,
+    game: Res<GameState>,
+    enable_pov_id: Res<EnablePovId>,
+    mut commands: Commands,
+) {
+    if !interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let side = table_side(game.seats(), game.current_player().as_usize());
+    for (entity, mut transform) in selected_query.iter_mut() {
+        transform.translation.y -= selection_offset(side);
+        commands.entity(entity).remove::<Selected>();
+    }
+    commands.run_system(enable_pov_id.0);
+}
+
+/// Lays a marker next to each player for every scopa they made this hand.
+fn update_scopa_markers(
+    game: Res<GameState>,
+    italian_assets: Res<ItalianAssets>,
+    markers: Query<Entity, With<ScopaMarker>>,
+    mut commands: Commands,
+) {
+    for entity in markers.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let mut made_by = vec![0; game.seats()];
+    for (player, card) in game.scopas() {
+        let side = table_side(game.seats(), player.as_usize());
+        let count = &mut made_by[player.as_usize()];
+        let direction = match side {
+            TableSide::Bottom => Vec3::X,
+            TableSide::Right => Vec3::Y,
+            TableSide::Top => Vec3::NEG_X,
+            TableSide::Left => Vec3::NEG_Y,
+        };
+        let offset = SCOPA_MARKER_OFFSET + *count as f32 * SCOPA_MARKER_SPACING;
+        let position = player_position(CANVAS_SIZE.x, CANVAS_SIZE.y, side) + direction * offset;
+        commands.spawn((
+            Sprite {
+                custom_size: Some(CARD_SIZE),
+                image: italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone(),
+                ..default()
+            },
+            Transform {
+                translation: position.with_z(*count as f32),
+                rotation: Quat::from_rotation_z(PI * 0.5),
+                ..default()
+            },
+            ScopaMarker,
+        ));
+        *count += 1;
+    }
+}
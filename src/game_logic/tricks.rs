@@ -0,0 +1,393 @@
+//! Looking back at the tricks of the hand being played: the last one, card by
+//! card, as the rules of Tressette allow, and the list of all of them.
+//!
+//! The engines only keep the cards played and what they did, so tricks are
+//! kept here as moves are made. When moves are taken back, or another game is
+//! put on the table, they're worked out again by dealing the game again and
+//! replaying its moves.
+
+use bevy::{
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, PositionType, Val},
+};
+use shuftlib::{tressette::TressetteCard, trick_taking::PlayerId};
+
+use super::{
+    AppState, AtTable, CARD_SIZE, FontHandle, GameState, ItalianAssets, card_name,
+    panel::{panel_node, spawn_button, spawn_panel},
+    player_label,
+};
+use crate::games::{MoveEffect, record::Move};
+
+/// Key showing the last trick.
+const LAST_TRICK_KEY: KeyCode = KeyCode::KeyL;
+
+/// Key showing the list of tricks.
+const HISTORY_KEY: KeyCode = KeyCode::KeyH;
+
+/// How much bigger than on the table the cards of the last trick are drawn.
+const CARD_SCALE: f32 = 2.0;
+
+pub(super) struct TricksPlugin;
+
+impl Plugin for TricksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HandTricks>().add_systems(
+            Update,
+            (
+                (handle_trick_buttons, handle_trick_keys)
+                    .after(track_tricks)
+                    .run_if(in_state(AtTable).and(not(in_state(AppState::Paused)))),
+                (track_tricks, refresh_trick_panels)
+                    .chain()
+                    .run_if(resource_changed::<GameState>),
+            ),
+        );
+    }
+}
+
+/// A trick of the hand, with who played each card, in order.
+struct Trick {
+    cards: Vec<(PlayerId, TressetteCard)>,
+    winner: PlayerId,
+}
+
+/// Tricks of the hand being played, or of the hand just completed until the
+/// first card of the next one is played, along with the moves they're made
+/// of.
+#[derive(Resource, Default)]
+struct HandTricks {
+    moves: Vec<Move>,
+    /// Who was to play when the last of `moves` was taken into account.
+    next_player: PlayerId,
+    tricks: Vec<Trick>,
+    cards: Vec<(PlayerId, TressetteCard)>,
+    hand_over: bool,
+}
+
+impl HandTricks {
+    /// Works the tricks out from the start of the game.
+    fn of(game: &GameState) -> Self {
+        let mut replayed = game
+            .variant
+            .deal_game(game.rules, game.seed, game.deal.as_ref());
+        let mut hand_tricks = HandTricks::default();
+        for mv in &game.moves {
+            let player = replayed.current_player();
+            if let Err(e) = replayed.apply(mv) {
+                warn!("Invalid recorded move: {:?}", e);
+                break;
+            }
+            hand_tricks.push(mv, player, replayed.last_effect());
+        }
+        hand_tricks
+    }
+
+    /// Takes a move made by `player` into account, given what it did.
+    fn push(&mut self, mv: &Move, player: PlayerId, effect: Option<MoveEffect>) {
+        self.moves.push(mv.clone());
+        let Move::Play(card) = mv else {
+            return;
+        };
+        if self.hand_over {
+            self.tricks.clear();
+            self.hand_over = false;
+        }
+        self.cards.push((player, *card));
+        let winner = match effect {
+            Some(MoveEffect::TrickCompleted { winner }) => winner,
+            Some(
+                MoveEffect::HandComplete { trick_winner } | MoveEffect::GameOver { trick_winner },
+            ) => {
+                self.hand_over = true;
+                trick_winner
+            }
+            _ => return,
+        };
+        self.tricks.push(Trick {
+            cards: std::mem::take(&mut self.cards),
+            winner,
+        });
+    }
+}
+
+/// Takes the move just made into account, or works the tricks out again when
+/// the game on the table is not the one they were kept for.
+fn track_tricks(game: Res<GameState>, mut hand_tricks: ResMut<HandTricks>) {
+    match game.moves.split_last() {
+        Some((last, before)) if before == hand_tricks.moves.as_slice() => {
+            let player = hand_tricks.next_player;
+            hand_tricks.push(last, player, game.last_effect());
+        }
+        _ if game.moves != hand_tricks.moves => *hand_tricks = HandTricks::of(&game),
+        _ => {}
+    }
+    hand_tricks.next_player = game.current_player();
+}
+
+/// What pressing one of the buttons in the corner shows, or hides.
+#[derive(Component, Clone, Copy)]
+pub(super) enum TrickButton {
+    LastTrick,
+    History,
+}
+
+/// Holds the buttons showing the tricks.
+#[derive(Component)]
+pub(super) struct TrickButtons;
+
+/// The cards of the last trick.
+#[derive(Component)]
+pub(super) struct LastTrickPanel;
+
+/// Every trick of the hand, one per line.
+#[derive(Component)]
+pub(super) struct TrickHistoryPanel;
+
+/// Spawns the buttons showing the tricks, above the undo button. Games played
+/// on a table, like Scopa, don't have tricks to show.
+pub(super) fn spawn_trick_buttons(commands: &mut Commands, font: &FontHandle) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(45.0),
+                left: Val::Px(10.0),
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            TrickButtons,
+        ))
+        .with_children(|parent| {
+            for (button, label) in [
+                (TrickButton::LastTrick, "Last trick (L)"),
+                (TrickButton::History, "Tricks (H)"),
+            ] {
+                spawn_button(parent, &text_font, label, button);
+            }
+        });
+}
+
+/// Fills the last trick panel: each card with who played it underneath, the
+/// winner's name highlighted.
+fn fill_last_trick(
+    parent: &mut ChildSpawnerCommands,
+    tricks: &[Trick],
+    font: &FontHandle,
+    italian_assets: &ItalianAssets,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    let Some(trick) = tricks.last() else {
+        parent.spawn((
+            Text::new("No trick played yet"),
+            text_font,
+            TextColor(Color::WHITE),
+        ));
+        return;
+    };
+    for (player, card) in &trick.cards {
+        let color = if *player == trick.winner {
+            Color::srgb(1.0, 0.85, 0.2)
+        } else {
+            Color::WHITE
+        };
+        parent
+            .spawn(Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(5.0),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn((
+                    Node {
+                        width: Val::Px(CARD_SIZE.x * CARD_SCALE),
+                        height: Val::Px(CARD_SIZE.y * CARD_SCALE),
+                        ..default()
+                    },
+                    ImageNode::new(
+                        italian_assets.0[card.suit() as usize][card.rank() as usize - 1].clone(),
+                    ),
+                ));
+                parent.spawn((
+                    Text::new(player_label(*player)),
+                    text_font.clone(),
+                    TextColor(color),
+                ));
+            });
+    }
+}
+
+/// Fills the list of tricks, each with its cards in the order they were
+/// played and who won it.
+fn fill_history(parent: &mut ChildSpawnerCommands, tricks: &[Trick], font: &FontHandle) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    parent.spawn((
+        Text::new("Tricks of this hand"),
+        text_font.clone(),
+        TextColor(Color::WHITE),
+    ));
+    for (index, trick) in tricks.iter().enumerate() {
+        let cards: Vec<String> = trick
+            .cards
+            .iter()
+            .map(|(player, card)| format!("{} {}", player_label(*player), card_name(*card)))
+            .collect();
+        parent.spawn((
+            Text::new(format!(
+                "{}: {}, won by {}",
+                index + 1,
+                cards.join(", "),
+                player_label(trick.winner)
+            )),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+    }
+}
+
+/// Shows the panel `button` is for, or hides it if it's shown.
+fn toggle_panel(
+    button: TrickButton,
+    hand_tricks: &HandTricks,
+    font: &FontHandle,
+    italian_assets: &ItalianAssets,
+    last_trick_query: &Query<Entity, With<LastTrickPanel>>,
+    history_query: &Query<Entity, With<TrickHistoryPanel>>,
+    commands: &mut Commands,
+) {
+    let shown = match button {
+        TrickButton::LastTrick => last_trick_query.iter().next(),
+        TrickButton::History => history_query.iter().next(),
+    };
+    if let Some(panel) = shown {
+        commands.entity(panel).despawn();
+        return;
+    }
+    let tricks = &hand_tricks.tricks;
+    match button {
+        TrickButton::LastTrick => {
+            spawn_panel(
+                commands,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(25.0),
+                    left: Val::Percent(35.0),
+                    column_gap: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                LastTrickPanel,
+            )
+            .with_children(|parent| fill_last_trick(parent, tricks, font, italian_assets));
+        }
+        TrickButton::History => {
+            spawn_panel(
+                commands,
+                Node {
+                    right: Val::Px(10.0),
+                    ..panel_node(Val::Px(40.0), Val::Auto)
+                },
+                TrickHistoryPanel,
+            )
+            .with_children(|parent| fill_history(parent, tricks, font));
+        }
+    }
+}
+
+fn handle_trick_buttons(
+    interaction_query: Query<(&Interaction, &TrickButton), Changed<Interaction>>,
+    hand_tricks: Res<HandTricks>,
+    font: Res<FontHandle>,
+    italian_assets: Res<ItalianAssets>,
+    last_trick_query: Query<Entity, With<LastTrickPanel>>,
+    history_query: Query<Entity, With<TrickHistoryPanel>>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            toggle_panel(
+                *button,
+                &hand_tricks,
+                &font,
+                &italian_assets,
+                &last_trick_query,
+                &history_query,
+                &mut commands,
+            );
+        }
+    }
+}
+
+fn handle_trick_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons_query: Query<(), With<TrickButtons>>,
+    hand_tricks: Res<HandTricks>,
+    font: Res<FontHandle>,
+    italian_assets: Res<ItalianAssets>,
+    last_trick_query: Query<Entity, With<LastTrickPanel>>,
+    history_query: Query<Entity, With<TrickHistoryPanel>>,
+    mut commands: Commands,
+) {
+    // Only where the buttons are, that is in games with tricks.
+    if buttons_query.is_empty() {
+        return;
+    }
+    for (key, button) in [
+        (LAST_TRICK_KEY, TrickButton::LastTrick),
+        (HISTORY_KEY, TrickButton::History),
+    ] {
+        if keys.just_pressed(key) {
+            toggle_panel(
+                button,
+                &hand_tricks,
+                &font,
+                &italian_assets,
+                &last_trick_query,
+                &history_query,
+                &mut commands,
+            );
+        }
+    }
+}
+
+/// Keeps the panels shown up to date as cards are played, or taken back.
+fn refresh_trick_panels(
+    hand_tricks: Res<HandTricks>,
+    font: Res<FontHandle>,
+    italian_assets: Res<ItalianAssets>,
+    last_trick_query: Query<Entity, With<LastTrickPanel>>,
+    history_query: Query<Entity, With<TrickHistoryPanel>>,
+    mut commands: Commands,
+) {
+    if last_trick_query.is_empty() && history_query.is_empty() {
+        return;
+    }
+    let tricks = &hand_tricks.tricks;
+    for panel in last_trick_query.iter() {
+        commands
+            .entity(panel)
+            .despawn_children()
+            .with_children(|parent| fill_last_trick(parent, tricks, &font, &italian_assets));
+    }
+    for panel in history_query.iter() {
+        commands
+            .entity(panel)
+            .despawn_children()
+            .with_children(|parent| fill_history(parent, tricks, &font));
+    }
+}
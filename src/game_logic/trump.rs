@@ -0,0 +1,162 @@
+//! Choice of the trump suit at the start of each hand of Marafone, and the
+//! trump indicator next to the score.
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Val},
+};
+use shuftlib::{core::Suit, trick_taking::PlayerId};
+
+use super::{
+    AnnouncementText, AppState, Card, FontHandle, GameState, NextTurnId, PovCard, SetupGameId,
+    SyncHandsId, Turn, announce,
+    panel::{panel_node, spawn_button, spawn_panel},
+    pending, player_label, suit_name,
+};
+use crate::games::{PendingPhase, record::Move};
+
+pub(super) struct TrumpPlugin;
+
+impl Plugin for TrumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                handle_trump_buttons.run_if(in_state(AppState::InGame)),
+                update_trump_text.run_if(resource_changed::<GameState>),
+            ),
+        )
+        .init_resource::<PovTrumpId>();
+    }
+}
+
+/// Shows the trump suit, next to the score.
+#[derive(Component)]
+pub(super) struct TrumpText;
+
+#[derive(Component)]
+pub(super) struct TrumpPanel;
+
+/// Chooses the suit as trump when pressed, declaring the maraffa if set.
+#[derive(Component)]
+struct TrumpButton {
+    suit: Suit,
+    maraffa: bool,
+}
+
+#[derive(Resource)]
+pub(super) struct PovTrumpId(pub(super) SystemId);
+impl FromWorld for PovTrumpId {
+    fn from_world(world: &mut World) -> Self {
+        let id = world.register_system(pov_trump);
+        PovTrumpId(id)
+    }
+}
+
+/// One shot system that lets the POV player choose trump.
+fn pov_trump(
+    game: Res<GameState>,
+    font: Res<FontHandle>,
+    mut commands: Commands,
+    mut pov_cards: Query<&mut Sprite, With<PovCard>>,
+) {
+    if game.pending_phase() != Some(PendingPhase::Trump) {
+        return;
+    }
+
+    // Nothing is played yet, show the whole hand.
+    for mut sprite in pov_cards.iter_mut() {
+        sprite.color = Color::WHITE;
+    }
+    commands.set_state(Turn::NonPovTurn);
+
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_panel(
+        &mut commands,
+        panel_node(Val::Percent(35.0), Val::Percent(35.0)),
+        TrumpPanel,
+    )
+    .with_children(|parent| {
+        parent.spawn((
+            Text::new("Choose trump"),
+            text_font.clone(),
+            TextColor(Color::WHITE),
+        ));
+        for mv in game.pending_moves() {
+            let Move::Trump { suit, maraffa } = mv else {
+                continue;
+            };
+            let label = if maraffa {
+                format!("{} (maraffa)", suit_name(suit))
+            } else {
+                suit_name(suit).to_string()
+            };
+            spawn_button(parent, &text_font, label, TrumpButton { suit, maraffa });
+        }
+    });
+}
+
+fn handle_trump_buttons(
+    interaction_query: Query<(&Interaction, &TrumpButton), Changed<Interaction>>,
+    panel_query: Query<Entity, With<TrumpPanel>>,
+    mut game: ResMut<GameState>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    card_query: Query<Entity, With<Card>>,
+    setup_game_id: Res<SetupGameId>,
+    sync_hands_id: Res<SyncHandsId>,
+    next_turn_id: Res<NextTurnId>,
+    mut commands: Commands,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let mv = Move::Trump {
+            suit: button.suit,
+            maraffa: button.maraffa,
+        };
+        match pending::make(&mut game, mv) {
+            Ok((message, outcome)) => {
+                for entity in panel_query.iter() {
+                    commands.entity(entity).despawn();
+                }
+                announce(&mut announcement_query, message);
+                pending::move_on(
+                    &mut commands,
+                    outcome,
+                    &card_query,
+                    &setup_game_id,
+                    &sync_hands_id,
+                    &next_turn_id,
+                );
+            }
+            Err(e) => warn!("Invalid trump: {:?}", e),
+        }
+    }
+}
+
+pub(super) fn trump_message(player: PlayerId, suit: Suit, maraffa: bool) -> String {
+    let message = format!("{}: trump is {}", player_label(player), suit_name(suit));
+    if maraffa {
+        format!("{message}, maraffa!")
+    } else {
+        message
+    }
+}
+
+/// Keeps the trump indicator in line with the game.
+fn update_trump_text(game: Res<GameState>, mut query: Query<&mut TextSpan, With<TrumpText>>) {
+    let label = match game.trump_suit() {
+        Some(suit) => format!("   Trump: {}", suit_name(suit)),
+        None => String::new(),
+    };
+    for mut span in query.iter_mut() {
+        **span = label.clone();
+    }
+}
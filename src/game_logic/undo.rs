@@ -0,0 +1,102 @@
+//! Taking back moves in games against bots: the last move of the POV player
+//! goes, along with whatever the bots did after it.
+
+use bevy::{
+    prelude::*,
+    text::TextFont,
+    ui::{Interaction, Node, PositionType, Val},
+};
+use shuftlib::tressette::Status;
+
+use super::{
+    AnnouncementText, AppState, Card, FontHandle, GameState, MovingTo, ScoreText, SetupGameId,
+    ToDespawn, Turn, accusi::AccusiPanel, announce, auction::AuctionPanel,
+    panel::spawn_lone_button, score_text, table::CapturePanel, trump::TrumpPanel,
+};
+
+pub(super) struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            handle_undo_button.run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+#[derive(Component)]
+pub(super) struct UndoButton;
+
+/// Spawns the button taking back the last move.
+pub(super) fn spawn_undo_button(commands: &mut Commands, font: &FontHandle) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+    spawn_lone_button(
+        commands,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        &text_font,
+        "Undo",
+        UndoButton,
+    );
+}
+
+/// Takes back the last move of the POV player when it's their turn again,
+/// and lays the table out as it was before it.
+fn handle_undo_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<UndoButton>)>,
+    state: Res<State<Turn>>,
+    mut game: ResMut<GameState>,
+    moving_query: Query<(), With<MovingTo>>,
+    card_query: Query<Entity, With<Card>>,
+    panel_query: Query<
+        Entity,
+        Or<(
+            With<AccusiPanel>,
+            With<AuctionPanel>,
+            With<CapturePanel>,
+            With<TrumpPanel>,
+        )>,
+    >,
+    mut score_text_query: Query<&mut Text, (With<ScoreText>, Without<AnnouncementText>)>,
+    mut announcement_query: Query<&mut Text, With<AnnouncementText>>,
+    setup_game_id: Res<SetupGameId>,
+    mut commands: Commands,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // Only while the POV player is thinking, not while cards are moving.
+        if *state.get() != Turn::PovTurn
+            || !moving_query.is_empty()
+            || matches!(game.status(), Status::Finished { .. })
+        {
+            continue;
+        }
+        if !game.undo() {
+            continue;
+        }
+
+        for entity in card_query.iter() {
+            commands.entity(entity).insert(ToDespawn);
+        }
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        if let Ok(mut text) = score_text_query.single_mut() {
+            *text = Text::new(score_text("Score", &game.score()));
+        }
+        announce(&mut announcement_query, "Move taken back".to_string());
+        commands.run_system(setup_game_id.0);
+    }
+}
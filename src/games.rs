@@ -0,0 +1,434 @@
+//! Client-side engines for the variants `shuftlib` doesn't provide, and the
+//! [`CardGame`] abstraction the table is drawn from.
+
+use bevy::prelude::Resource;
+use rand::{RngExt, rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use shuftlib::{
+    core::{Suit, deck::Deck, italian::ItalianRank},
+    tressette::{self, Error, Game, Status, TressetteCard, TressetteRules},
+    trick_taking::{OngoingHand, OngoingTrick, PLAYERS, Player, PlayerId, TrickTakingGame},
+};
+
+use accusi::Accuso;
+use deal::Deal;
+use record::{InvalidMove, Move};
+
+pub mod accusi;
+pub mod briscola;
+pub mod deal;
+pub mod marafone;
+pub mod record;
+pub mod rovescino;
+pub mod scopa;
+pub mod terziglio;
+pub mod tressette_due;
+pub mod tressette_quattro;
+
+#[cfg(test)]
+pub(crate) mod fixtures;
+
+/// The effect of playing a card, shared by every variant.
+///
+/// Unlike [`tressette::MoveEffect`] it doesn't carry the score, since not every
+/// variant is played by two teams. Ask the game for its score instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveEffect {
+    /// A card was played, but the trick is not yet complete.
+    CardPlayed,
+    /// A trick was completed, and the winner is now the next to play. In
+    /// games played on a table, `winner` captured some of its cards instead.
+    TrickCompleted { winner: PlayerId },
+    /// A hand was completed, scores were updated, and a new hand was dealt.
+    HandComplete { trick_winner: PlayerId },
+    /// The game is over.
+    GameOver { trick_winner: PlayerId },
+}
+
+impl From<tressette::MoveEffect> for MoveEffect {
+    fn from(effect: tressette::MoveEffect) -> Self {
+        match effect {
+            tressette::MoveEffect::CardPlayed => MoveEffect::CardPlayed,
+            tressette::MoveEffect::TrickCompleted { winner } => {
+                MoveEffect::TrickCompleted { winner }
+            }
+            tressette::MoveEffect::HandComplete { trick_winner, .. } => {
+                MoveEffect::HandComplete { trick_winner }
+            }
+            tressette::MoveEffect::GameOver { trick_winner, .. } => {
+                MoveEffect::GameOver { trick_winner }
+            }
+        }
+    }
+}
+
+/// Something the current player has to do at the table besides, or before,
+/// playing a card. It's done with one of the game's
+/// [`pending_moves`](CardGame::pending_moves), made with
+/// [`apply`](CardGame::apply).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingPhase {
+    /// Bidding to play alone, as in Terziglio.
+    Bid,
+    /// Asking for a Three after calling to play alone.
+    CallThree,
+    /// Giving `to` a card for the Three they handed over.
+    GiveBack { to: PlayerId },
+    /// Discarding `count` cards picked from the hand.
+    Discard { count: usize },
+    /// Choosing the trump suit before the play, as in Marafone.
+    Trump,
+    /// Declaring the accusi held, before playing the first card.
+    Accusi,
+}
+
+/// House rules of Tressette, chosen before the game starts.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RulesConfig {
+    /// Points a team needs to win the game.
+    pub target: u8,
+    /// Whether the last trick of each hand is worth an extra point.
+    pub last_trick_bonus: bool,
+    /// What happens to the thirds of a point that don't make a whole point.
+    pub thirds: Thirds,
+    /// Whether players can declare accusi.
+    pub accusi: bool,
+}
+
+impl RulesConfig {
+    /// Targets the game can be played to.
+    pub const TARGETS: [u8; 4] = [11, 21, 31, 41];
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            target: 31,
+            last_trick_bonus: true,
+            thirds: Thirds::Dropped,
+            accusi: true,
+        }
+    }
+}
+
+/// Handling of the thirds left over once a team's cards are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Thirds {
+    /// Lost at the end of each hand.
+    Dropped,
+    /// Kept for the team and counted with the next hand.
+    Carried,
+}
+
+/// A line of the summary shown at the end of a hand: what it's for and the
+/// points each side made with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryLine {
+    pub label: String,
+    pub points: Vec<u8>,
+}
+
+impl SummaryLine {
+    pub fn new(label: &str, points: &[u8]) -> Self {
+        Self {
+            label: label.to_string(),
+            points: points.to_vec(),
+        }
+    }
+}
+
+/// Lines of a hand summary telling what the cards each side took are worth
+/// in Tressette: how many they are, the aces, a point each, and the thirds of
+/// a point the twos, threes and figures are worth.
+pub fn card_lines(captured: &[Vec<TressetteCard>]) -> Vec<SummaryLine> {
+    let cards: Vec<u8> = captured.iter().map(|cards| cards.len() as u8).collect();
+    let aces: Vec<u8> = captured
+        .iter()
+        .map(|cards| {
+            cards
+                .iter()
+                .filter(|c| c.rank() == ItalianRank::Ace)
+                .count() as u8
+        })
+        .collect();
+    let others: Vec<u8> = captured
+        .iter()
+        .map(|cards| {
+            cards
+                .iter()
+                .filter(|c| c.rank() != ItalianRank::Ace)
+                .map(|c| thirds(*c))
+                .sum()
+        })
+        .collect();
+    vec![
+        SummaryLine::new("Cards taken", &cards),
+        SummaryLine::new("Aces", &aces),
+        SummaryLine::new("Thirds from twos, threes and figures", &others),
+    ]
+}
+
+/// A game played with the 40 cards Italian deck, as seen by the table.
+///
+/// Every engine deals its own hands when created and after each hand is
+/// complete, so the table only needs to ask what's in each hand, who's next
+/// and what playing a card did.
+pub trait CardGame: Send + Sync {
+    /// Number of seats at the table.
+    fn seats(&self) -> usize;
+
+    /// Returns the cards in the given player's hand.
+    fn hand(&self, player: PlayerId) -> &[TressetteCard];
+
+    /// Returns the player whose turn it is to move.
+    fn current_player(&self) -> PlayerId;
+
+    /// Returns the cards played in the ongoing trick, indexed by player.
+    fn current_trick(&self) -> &[Option<TressetteCard>];
+
+    /// Returns the player who led the current trick.
+    fn trick_leader(&self) -> PlayerId;
+
+    /// Returns all legal cards for the current player.
+    fn legal_cards(&self) -> Vec<TressetteCard>;
+
+    /// Plays a card for the current player.
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error>;
+
+    /// Effect of the last card played, if any.
+    fn last_effect(&self) -> Option<MoveEffect>;
+
+    /// Score of each team, or of each player when there are no teams.
+    fn score(&self) -> Vec<u8>;
+
+    /// Returns the current status of the game.
+    fn status(&self) -> Status;
+
+    /// Number of cards left to draw from, zero for games without a stock.
+    fn stock_len(&self) -> usize {
+        0
+    }
+
+    /// What the current player has to do besides playing a card, if anything.
+    fn pending_phase(&self) -> Option<PendingPhase> {
+        None
+    }
+
+    /// Moves the current player can make in the pending phase. Discards
+    /// aren't listed, any cards of the hand can be picked.
+    fn pending_moves(&self) -> Vec<Move> {
+        Vec::new()
+    }
+
+    /// Move a bot makes in the pending phase.
+    fn bot_move(&self) -> Option<Move> {
+        None
+    }
+
+    /// Makes `mv` for the current player. Games without a pending phase only
+    /// have cards to play.
+    fn apply(&mut self, mv: &Move) -> Result<(), InvalidMove> {
+        match mv {
+            Move::Play(card) if self.play_card(*card).is_ok() => Ok(()),
+            _ => Err(InvalidMove(mv.clone())),
+        }
+    }
+
+    /// Whether cards drawn from the stock are shown to the whole table.
+    fn shows_drawn_cards(&self) -> bool {
+        false
+    }
+
+    /// Card turned face up to show the trump suit, for games that have one.
+    fn trump(&self) -> Option<TressetteCard> {
+        None
+    }
+
+    /// Trump suit of the ongoing hand, whether or not a card shows it.
+    fn trump_suit(&self) -> Option<Suit> {
+        self.trump().map(|c| c.suit())
+    }
+
+    /// Cards lying face up on the table, for games where cards are captured
+    /// from it rather than played in tricks.
+    fn table_cards(&self) -> Option<&[TressetteCard]> {
+        None
+    }
+
+    /// Every set of table cards `card` could capture.
+    fn capture_options(&self, _card: TressetteCard) -> Vec<Vec<TressetteCard>> {
+        Vec::new()
+    }
+
+    /// Sweeps of the table made this hand, with the card that made each one.
+    fn scopas(&self) -> &[(PlayerId, TressetteCard)] {
+        &[]
+    }
+
+    /// Accusi declared this hand, in the order they were declared.
+    fn declared(&self) -> &[(PlayerId, Accuso)] {
+        &[]
+    }
+
+    /// Breakdown of the points made in the last completed hand, empty for
+    /// games that don't keep one.
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &[]
+    }
+
+    /// Points each side made in the last completed hand, empty for games
+    /// that don't keep them.
+    fn hand_points(&self) -> &[u8] {
+        &[]
+    }
+
+    /// Side of the score that made cappotto in the last completed hand, in
+    /// games where it can be made.
+    fn cappotto(&self) -> Option<usize> {
+        None
+    }
+
+    /// Seat whose hand lies face up on the table, played by the player
+    /// sitting across from it.
+    fn dummy(&self) -> Option<PlayerId> {
+        None
+    }
+
+    /// Player who dealt the hand being played, in games where somebody deals.
+    fn dealer(&self) -> Option<PlayerId> {
+        None
+    }
+
+    /// Player choosing the cards `player` plays: the one across the table for
+    /// the dummy, the player themselves otherwise.
+    fn controller(&self, player: PlayerId) -> PlayerId {
+        if self.dummy() == Some(player) {
+            PlayerId::try_from((player.as_usize() + self.seats() / 2) % self.seats()).unwrap()
+        } else {
+            player
+        }
+    }
+}
+
+impl CardGame for Game {
+    fn seats(&self) -> usize {
+        PLAYERS
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        Game::hand(self, player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        Game::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        Game::current_trick(self)
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        Game::trick_leader(self)
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        Game::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        Game::play_card(self, card).map(MoveEffect::from)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.history().last().map(|(_, effect)| (*effect).into())
+    }
+
+    fn score(&self) -> Vec<u8> {
+        let (team_0, team_1) = Game::score(self);
+        vec![team_0, team_1]
+    }
+
+    fn status(&self) -> Status {
+        Game::status(self)
+    }
+}
+
+/// Value of a card in thirds of a point.
+pub fn thirds(card: tressette::TressetteCard) -> u8 {
+    (card.value() * 3).to_integer() as u8
+}
+
+/// Side that took every trick of a hand, given the cards each side took:
+/// the only one that took any.
+pub fn took_every_trick(captured: &[Vec<TressetteCard>]) -> Option<usize> {
+    let mut taking = captured
+        .iter()
+        .enumerate()
+        .filter(|(_, cards)| !cards.is_empty());
+    match (taking.next(), taking.next()) {
+        (Some((side, _)), None) => Some(side),
+        _ => None,
+    }
+}
+
+/// Shuffles a full deck with `rng`, so that games started from the same seed
+/// deal the same hands.
+pub fn shuffled_deck(rng: &mut StdRng) -> Deck<TressetteCard> {
+    let mut cards: Vec<TressetteCard> = TressetteRules::deck().into_iter().collect();
+    cards.shuffle(rng);
+    Deck::from(cards)
+}
+
+/// Deals a new four-player Tressette game from a deck shuffled with `rng`,
+/// the way [`Game::new`] deals it from an unseeded one.
+pub fn deal_tressette(rng: &mut StdRng) -> Game {
+    let dealer = PlayerId::try_from(rng.random_range(0..PLAYERS)).unwrap();
+    let mut hands: [Vec<TressetteCard>; PLAYERS] = Default::default();
+    let mut deck = shuffled_deck(rng);
+    let mut to_deal_to = dealer + 1;
+    while let Some(cards) = deck.draw_n(5) {
+        hands[to_deal_to.as_usize()].extend(cards);
+        to_deal_to.inc();
+    }
+    tressette_game(hands, dealer + 1)
+}
+
+/// Sets up a four-player Tressette game from the cards in `deal`.
+pub fn tressette_from_deal(deal: &Deal) -> Game {
+    tressette_game(deal.hands().clone(), deal.leader())
+}
+
+/// Seat right before `player` at a table of four, the dealer's when `player`
+/// leads the first trick of a hand.
+fn seat_before(player: PlayerId) -> PlayerId {
+    PlayerId::try_from((player.as_usize() + PLAYERS - 1) % PLAYERS).unwrap()
+}
+
+fn tressette_game(hands: [Vec<TressetteCard>; PLAYERS], leader: PlayerId) -> Game {
+    let mut players = [
+        PlayerId::PLAYER_0,
+        PlayerId::PLAYER_1,
+        PlayerId::PLAYER_2,
+        PlayerId::PLAYER_3,
+    ]
+    .map(Player::new);
+    for (player, hand) in players.iter_mut().zip(hands) {
+        for card in hand {
+            player.give(card);
+        }
+    }
+    // The dealer sits right before the leader.
+    let dealer = seat_before(leader);
+
+    let mut current_hand = OngoingHand::new();
+    current_hand.set_current_trick(Some(OngoingTrick::new(leader)));
+    Game::builder()
+        .deck(Deck::from(Vec::new()))
+        .players(players)
+        .current_hand(current_hand)
+        .dealing_player(dealer)
+        .score((0, 0))
+        .completed_hands(Vec::new())
+        .history(Vec::new())
+        .build()
+}
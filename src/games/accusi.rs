@@ -0,0 +1,110 @@
+//! Accusi, the declarations of Tressette.
+//!
+//! Before playing their first card of a hand, a player may declare some
+//! combinations of Aces, Twos and Threes held in hand:
+//!
+//! - the Napoletana: Ace, Two and Three of the same suit, worth 3 points;
+//! - the Buongioco: three or four Aces, Twos or Threes, worth as many points
+//!   as the cards.
+
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::TressetteCard,
+};
+
+/// Ranks accusi are made of.
+const RANKS: [ItalianRank; 3] = [ItalianRank::Ace, ItalianRank::Two, ItalianRank::Three];
+
+const SUITS: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+/// Points the Napoletana is worth.
+const NAPOLETANA_POINTS: u8 = 3;
+
+/// A declaration made with the cards in hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accuso {
+    /// Ace, Two and Three of the same suit.
+    Napoletana(Suit),
+    /// Three or four cards of the same rank.
+    Buongioco { rank: ItalianRank, count: u8 },
+}
+
+impl Accuso {
+    /// Points the declaration is worth.
+    pub fn points(self) -> u8 {
+        match self {
+            Accuso::Napoletana(_) => NAPOLETANA_POINTS,
+            Accuso::Buongioco { count, .. } => count,
+        }
+    }
+}
+
+/// Returns every declaration that can be made with `hand`.
+pub fn accusi(hand: &[TressetteCard]) -> Vec<Accuso> {
+    let napoletane = SUITS
+        .into_iter()
+        .filter(|suit| {
+            RANKS
+                .iter()
+                .all(|rank| hand.contains(&TressetteCard::new(*rank, *suit)))
+        })
+        .map(Accuso::Napoletana);
+    let buongiochi = RANKS.into_iter().filter_map(|rank| {
+        let count = hand.iter().filter(|c| c.rank() == rank).count() as u8;
+        (count >= 3).then_some(Accuso::Buongioco { rank, count })
+    });
+    napoletane.chain(buongiochi).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn napoletana() {
+        let hand = [
+            TressetteCard::new(ItalianRank::Three, Suit::Clubs),
+            TressetteCard::new(ItalianRank::King, Suit::Hearts),
+            TressetteCard::new(ItalianRank::Ace, Suit::Clubs),
+            TressetteCard::new(ItalianRank::Two, Suit::Clubs),
+            TressetteCard::new(ItalianRank::Two, Suit::Spades),
+        ];
+        assert_eq!(accusi(&hand), [Accuso::Napoletana(Suit::Clubs)]);
+        assert_eq!(Accuso::Napoletana(Suit::Clubs).points(), 3);
+        // Two cards of the suit aren't enough.
+        assert_eq!(accusi(&hand[1..]), []);
+    }
+
+    #[test]
+    fn buongioco_of_three() {
+        let hand = [
+            TressetteCard::new(ItalianRank::Two, Suit::Hearts),
+            TressetteCard::new(ItalianRank::Two, Suit::Diamonds),
+            TressetteCard::new(ItalianRank::Seven, Suit::Clubs),
+            TressetteCard::new(ItalianRank::Two, Suit::Spades),
+        ];
+        let buongioco = Accuso::Buongioco {
+            rank: ItalianRank::Two,
+            count: 3,
+        };
+        assert_eq!(accusi(&hand), [buongioco]);
+        assert_eq!(buongioco.points(), 3);
+    }
+
+    #[test]
+    fn buongioco_of_four() {
+        let hand = SUITS.map(|suit| TressetteCard::new(ItalianRank::Ace, suit));
+        let buongioco = Accuso::Buongioco {
+            rank: ItalianRank::Ace,
+            count: 4,
+        };
+        assert_eq!(accusi(&hand), [buongioco]);
+        assert_eq!(buongioco.points(), 4);
+    }
+
+    #[test]
+    fn figures_make_no_buongioco() {
+        let hand = SUITS.map(|suit| TressetteCard::new(ItalianRank::King, suit));
+        assert_eq!(accusi(&hand), []);
+    }
+}
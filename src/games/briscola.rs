@@ -0,0 +1,551 @@
+//! Briscola, for two players or for four players in two teams.
+//!
+//! Each player gets 3 cards and the next one is turned face up: its suit is
+//! the trump ("briscola") and the card itself goes under the stock, to be
+//! drawn last. There's no need to follow suit: the highest trump takes the
+//! trick, or the highest card of the led suit if no trump was played. After
+//! every trick each player draws a card, starting from the winner.
+//!
+//! Aces are worth 11 points, Threes 10, Kings 4, Knights 3 and Jacks 2, for
+//! 120 points in a hand. Whoever takes more than 60 wins the hand, and the
+//! first side to win two hands wins the game.
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::{Error, Status, TressetteCard},
+    trick_taking::PlayerId,
+};
+
+use super::{CardGame, MoveEffect, SummaryLine, shuffled_deck};
+
+/// Number of cards dealt to each player at the start of a hand.
+const HAND_SIZE: usize = 3;
+
+/// Points a side needs to win a hand, out of 120.
+const POINTS_TO_WIN_HAND: u16 = 61;
+
+/// Hands a side needs to win the game.
+const HANDS_TO_WIN: u8 = 2;
+
+#[derive(Debug)]
+pub struct Briscola {
+    seats: usize,
+    rng: StdRng,
+    /// Cards left to draw, the last one being drawn first. The trump card
+    /// lies at the bottom.
+    stock: Vec<TressetteCard>,
+    trump: TressetteCard,
+    hands: Vec<Vec<TressetteCard>>,
+    trick: Vec<Option<TressetteCard>>,
+    captured: [Vec<TressetteCard>; 2],
+    leader: PlayerId,
+    dealer: PlayerId,
+    score: [u8; 2],
+    history: Vec<(TressetteCard, MoveEffect)>,
+    /// Card points each side took in the last hand completed, the score
+    /// counting hands won.
+    hand_points: [u8; 2],
+    /// What each side made in the last hand completed.
+    summary: Vec<SummaryLine>,
+}
+
+impl Briscola {
+    /// Creates a new game for `seats` players, 2 or 4, with the first hand
+    /// already dealt. Games created from the same `seed` deal the same cards.
+    pub fn new(seats: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dealer = seat(rng.random_range(0..seats));
+        let mut game = Self::seated(seats, rng, dealer);
+        game.deal();
+        game
+    }
+
+    /// Creates a new game with the first hand dealt as in `hands`, `stock`
+    /// left to draw from its end, `trump` turned up and `leader` playing
+    /// first. Until it's drawn, the trump card is the first of `stock`. The
+    /// hands after it are shuffled from `seed`.
+    pub fn from_deal(
+        hands: Vec<Vec<TressetteCard>>,
+        stock: Vec<TressetteCard>,
+        trump: TressetteCard,
+        leader: PlayerId,
+        seed: u64,
+    ) -> Self {
+        let seats = hands.len();
+        let dealer = seat((leader.as_usize() + seats - 1) % seats);
+        Self {
+            hands,
+            stock,
+            trump,
+            leader,
+            ..Self::seated(seats, StdRng::seed_from_u64(seed), dealer)
+        }
+    }
+
+    /// A game for `seats` players with no card dealt yet and `dealer` to
+    /// deal.
+    fn seated(seats: usize, rng: StdRng, dealer: PlayerId) -> Self {
+        assert!(
+            seats == 2 || seats == 4,
+            "Briscola is played by 2 or 4 players"
+        );
+        Self {
+            seats,
+            rng,
+            stock: Vec::new(),
+            trump: TressetteCard::new(ItalianRank::Ace, Suit::Hearts),
+            hands: vec![Vec::new(); seats],
+            trick: vec![None; seats],
+            captured: Default::default(),
+            leader: dealer,
+            dealer,
+            score: [0; 2],
+            history: Vec::new(),
+            hand_points: [0; 2],
+            summary: Vec::new(),
+        }
+    }
+
+    /// Returns the player whose turn it is to move.
+    pub fn current_player(&self) -> PlayerId {
+        let played = self.trick.iter().flatten().count();
+        self.next_by(self.leader, played)
+    }
+
+    /// Returns the hands won by each side: by each player with two players,
+    /// by each team with four.
+    pub fn score(&self) -> [u8; 2] {
+        self.score
+    }
+
+    /// Returns the current status of the game.
+    pub fn status(&self) -> Status {
+        match self.score.iter().position(|hands| *hands >= HANDS_TO_WIN) {
+            Some(winner) => Status::Finished {
+                winner: winner as u8,
+            },
+            None => Status::Ongoing,
+        }
+    }
+
+    /// Returns the card turned face up under the stock, whose suit is trump.
+    pub fn trump(&self) -> TressetteCard {
+        self.trump
+    }
+
+    /// Returns the cards in the given player's hand.
+    pub fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        &self.hands[player.as_usize()]
+    }
+
+    /// Returns the cards played in the ongoing trick, indexed by player.
+    pub fn current_trick(&self) -> &[Option<TressetteCard>] {
+        &self.trick
+    }
+
+    /// Returns the player who led the current trick.
+    pub fn trick_leader(&self) -> PlayerId {
+        self.leader
+    }
+
+    /// Returns the number of cards left in the stock, trump card included.
+    pub fn stock_len(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// Returns all legal cards for the current player: the whole hand, since
+    /// there's no need to follow suit.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+        self.hands[self.current_player().as_usize()].clone()
+    }
+
+    /// Plays a card for the current player, drawing from the stock once the
+    /// trick is complete.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+
+        let player = self.current_player();
+        if !self.hand(player).contains(&card) {
+            return Err(Error::CardNotInHand { card, player });
+        }
+
+        self.hands[player.as_usize()].retain(|&c| c != card);
+        self.trick[player.as_usize()] = Some(card);
+
+        let effect = if self.trick.iter().all(Option::is_some) {
+            self.complete_trick()
+        } else {
+            MoveEffect::CardPlayed
+        };
+        self.history.push((card, effect));
+
+        Ok(effect)
+    }
+
+    /// Returns the card history.
+    pub fn history(&self) -> &[(TressetteCard, MoveEffect)] {
+        &self.history
+    }
+
+    fn complete_trick(&mut self) -> MoveEffect {
+        let led = self.trick[self.leader.as_usize()].map(|c| c.suit());
+        let trump = self.trump.suit();
+        // Trumps beat every other suit, then the led suit beats the rest.
+        let winner = self
+            .trick
+            .iter()
+            .enumerate()
+            .filter_map(|(player, card)| card.map(|c| (player, c)))
+            .max_by_key(|(_, card)| {
+                let suit_rank = if card.suit() == trump {
+                    2
+                } else if Some(card.suit()) == led {
+                    1
+                } else {
+                    0
+                };
+                (suit_rank, strength(*card))
+            })
+            .map_or(self.leader, |(player, _)| seat(player));
+
+        let side = winner.as_usize() % 2;
+        self.captured[side].extend(self.trick.iter().flatten());
+        self.trick = vec![None; self.seats];
+        self.leader = winner;
+
+        // Everybody draws, starting from the winner.
+        for offset in 0..self.seats {
+            let player = self.next_by(winner, offset);
+            if let Some(card) = self.stock.pop() {
+                self.hands[player.as_usize()].push(card);
+            }
+        }
+
+        if self.hands.iter().all(Vec::is_empty) {
+            self.complete_hand(winner)
+        } else {
+            MoveEffect::TrickCompleted { winner }
+        }
+    }
+
+    fn complete_hand(&mut self, last_trick_winner: PlayerId) -> MoveEffect {
+        let points = self
+            .captured
+            .each_ref()
+            .map(|cards| cards.iter().map(|c| points(*c) as u16).sum::<u16>());
+        // Worth 120 at most, the points of a hand fit a u8.
+        self.hand_points = points.map(|p| p as u8);
+        // A 60 - 60 hand goes to nobody.
+        let mut hand = [0; 2];
+        if let Some(side) = points.iter().position(|p| *p >= POINTS_TO_WIN_HAND) {
+            hand[side] = 1;
+            self.score[side] += 1;
+        }
+
+        let cards = self.captured.each_ref().map(|cards| cards.len() as u8);
+        self.summary = vec![
+            SummaryLine::new("Cards taken", &cards),
+            SummaryLine::new("Card points", &points.map(|p| p as u8)),
+            SummaryLine::new("Hand", &hand),
+            SummaryLine::new("Total", &self.score),
+        ];
+
+        if matches!(self.status(), Status::Finished { .. }) {
+            MoveEffect::GameOver {
+                trick_winner: last_trick_winner,
+            }
+        } else {
+            self.dealer = self.next_by(self.dealer, 1);
+            self.deal();
+            MoveEffect::HandComplete {
+                trick_winner: last_trick_winner,
+            }
+        }
+    }
+
+    /// Shuffles a full deck, deals every hand and turns the trump card, which
+    /// goes under the stock.
+    fn deal(&mut self) {
+        let mut deck = shuffled_deck(&mut self.rng);
+
+        self.hands = vec![Vec::new(); self.seats];
+        self.captured = Default::default();
+        for offset in 1..=self.seats {
+            let player = self.next_by(self.dealer, offset);
+            if let Some(cards) = deck.draw_n(HAND_SIZE) {
+                self.hands[player.as_usize()].extend(cards);
+            }
+        }
+
+        self.stock = deck.into_iter().collect();
+        self.trump = self.stock.pop().unwrap();
+        self.stock.insert(0, self.trump);
+
+        self.leader = self.next_by(self.dealer, 1);
+        self.trick = vec![None; self.seats];
+    }
+
+    /// Returns the player `offset` seats after `player`.
+    fn next_by(&self, player: PlayerId, offset: usize) -> PlayerId {
+        seat((player.as_usize() + offset) % self.seats)
+    }
+}
+
+impl CardGame for Briscola {
+    fn seats(&self) -> usize {
+        self.seats
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        Briscola::hand(self, player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        Briscola::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        Briscola::current_trick(self)
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        Briscola::trick_leader(self)
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        Briscola::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        Briscola::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.history().last().map(|(_, effect)| *effect)
+    }
+
+    fn score(&self) -> Vec<u8> {
+        Briscola::score(self).to_vec()
+    }
+
+    fn status(&self) -> Status {
+        Briscola::status(self)
+    }
+
+    fn stock_len(&self) -> usize {
+        Briscola::stock_len(self)
+    }
+
+    fn trump(&self) -> Option<TressetteCard> {
+        Some(self.trump)
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+
+    fn dealer(&self) -> Option<PlayerId> {
+        Some(self.dealer)
+    }
+}
+
+/// Points a card is worth in Briscola.
+fn points(card: TressetteCard) -> u8 {
+    match card.rank() {
+        ItalianRank::Ace => 11,
+        ItalianRank::Three => 10,
+        ItalianRank::King => 4,
+        ItalianRank::Knight => 3,
+        ItalianRank::Jack => 2,
+        _ => 0,
+    }
+}
+
+/// Strength of a card within its suit: Ace, Three, King, Knight, Jack, then
+/// from Seven down to Two.
+fn strength(card: TressetteCard) -> u8 {
+    match card.rank() {
+        ItalianRank::Ace => 10,
+        ItalianRank::Three => 9,
+        ItalianRank::King => 8,
+        ItalianRank::Knight => 7,
+        ItalianRank::Jack => 6,
+        rank => rank as u8 - 2,
+    }
+}
+
+/// Returns the player sitting at `index`.
+fn seat(index: usize) -> PlayerId {
+    PlayerId::try_from(index).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game for `seats` players with `hands` dealt, Hearts as trump and an
+    /// empty stock, player 0 leading.
+    fn game_with(hands: Vec<Vec<TressetteCard>>) -> Briscola {
+        let trump = TressetteCard::new(ItalianRank::Seven, Suit::Hearts);
+        Briscola::from_deal(hands, Vec::new(), trump, seat(0), 0)
+    }
+
+    /// Cards worth 30 points in `suit`: its Ace, Three, King, Knight and
+    /// Jack.
+    fn thirty_points(suit: Suit) -> Vec<TressetteCard> {
+        [
+            ItalianRank::Ace,
+            ItalianRank::Three,
+            ItalianRank::King,
+            ItalianRank::Knight,
+            ItalianRank::Jack,
+        ]
+        .map(|rank| TressetteCard::new(rank, suit))
+        .to_vec()
+    }
+
+    /// Plays the `i`-th card of every hand in turn, for as many tricks as
+    /// the hands are long, and returns the effect of the last card.
+    fn play_out(game: &mut Briscola, hands: &[Vec<TressetteCard>]) -> MoveEffect {
+        let mut effect = MoveEffect::CardPlayed;
+        for i in 0..hands[0].len() {
+            for _ in hands {
+                let card = hands[game.current_player().as_usize()][i];
+                effect = game.play_card(card).unwrap();
+            }
+        }
+        effect
+    }
+
+    #[test]
+    fn lowest_trump_beats_the_led_suit() {
+        let mut game = game_with(vec![
+            vec![
+                TressetteCard::new(ItalianRank::Ace, Suit::Spades),
+                TressetteCard::new(ItalianRank::Four, Suit::Clubs),
+            ],
+            vec![
+                TressetteCard::new(ItalianRank::Two, Suit::Hearts),
+                TressetteCard::new(ItalianRank::Ace, Suit::Diamonds),
+            ],
+        ]);
+        game.play_card(TressetteCard::new(ItalianRank::Ace, Suit::Spades))
+            .unwrap();
+        assert_eq!(
+            game.play_card(TressetteCard::new(ItalianRank::Two, Suit::Hearts)),
+            Ok(MoveEffect::TrickCompleted { winner: seat(1) })
+        );
+
+        // Without trumps, a card of another suit never takes the trick.
+        game.play_card(TressetteCard::new(ItalianRank::Ace, Suit::Diamonds))
+            .unwrap();
+        assert_eq!(
+            game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Clubs)),
+            Ok(MoveEffect::HandComplete {
+                trick_winner: seat(1)
+            })
+        );
+    }
+
+    #[test]
+    fn trump_card_is_drawn_last() {
+        let game = Briscola::new(2, 0);
+        assert_eq!(game.stock.first(), Some(&game.trump()));
+        assert_eq!(game.stock_len(), 40 - 2 * HAND_SIZE);
+
+        let trump = TressetteCard::new(ItalianRank::Seven, Suit::Hearts);
+        let mut game = Briscola::from_deal(
+            vec![
+                vec![TressetteCard::new(ItalianRank::Ace, Suit::Spades)],
+                vec![TressetteCard::new(ItalianRank::Four, Suit::Spades)],
+            ],
+            vec![trump, TressetteCard::new(ItalianRank::King, Suit::Clubs)],
+            trump,
+            seat(0),
+            0,
+        );
+        game.play_card(TressetteCard::new(ItalianRank::Ace, Suit::Spades))
+            .unwrap();
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Spades))
+            .unwrap();
+        // The winner draws first, leaving the trump card to the other player.
+        assert_eq!(
+            game.hand(seat(0)),
+            [TressetteCard::new(ItalianRank::King, Suit::Clubs)]
+        );
+        assert_eq!(game.hand(seat(1)), [trump]);
+        assert_eq!(game.stock_len(), 0);
+    }
+
+    #[test]
+    fn hand_goes_to_the_side_with_more_than_sixty() {
+        // Player 0 leads the Hearts worth 30 points, which are trumps, and
+        // player 1 throws the Diamonds worth as much on them.
+        let hands = |last: ItalianRank| {
+            let mut hands = vec![thirty_points(Suit::Hearts), thirty_points(Suit::Diamonds)];
+            hands[0].push(TressetteCard::new(last, Suit::Clubs));
+            hands[1].push(TressetteCard::new(ItalianRank::Five, Suit::Clubs));
+            hands
+        };
+
+        let mut game = game_with(hands(ItalianRank::Four));
+        play_out(&mut game, &hands(ItalianRank::Four));
+        // 60 points are not enough to win the hand.
+        assert_eq!(game.score(), [0, 0]);
+
+        let mut game = game_with(hands(ItalianRank::Jack));
+        play_out(&mut game, &hands(ItalianRank::Jack));
+        assert_eq!(game.score(), [1, 0]);
+    }
+
+    #[test]
+    fn partners_score_together_with_four_players() {
+        let worthless = |suit| {
+            [
+                ItalianRank::Two,
+                ItalianRank::Four,
+                ItalianRank::Five,
+                ItalianRank::Six,
+                ItalianRank::Seven,
+            ]
+            .map(|rank| TressetteCard::new(rank, suit))
+            .to_vec()
+        };
+        // Player 0 takes a trick with each Spade worth points and player 1
+        // throws the Diamonds worth as much on them, 60 points in all.
+        let mut hands = vec![
+            thirty_points(Suit::Spades),
+            thirty_points(Suit::Diamonds),
+            worthless(Suit::Diamonds),
+            worthless(Suit::Spades),
+        ];
+        for (hand, rank) in hands.iter_mut().zip([
+            ItalianRank::Four,
+            ItalianRank::Five,
+            ItalianRank::Ace,
+            ItalianRank::Six,
+        ]) {
+            hand.push(TressetteCard::new(rank, Suit::Clubs));
+        }
+
+        let mut game = game_with(hands.clone());
+        assert_eq!(
+            play_out(&mut game, &hands),
+            MoveEffect::HandComplete {
+                trick_winner: seat(2)
+            }
+        );
+        // Player 2 takes the Ace for the team of player 0.
+        assert_eq!(game.score(), [1, 0]);
+    }
+}
@@ -0,0 +1,223 @@
+//! Deals laid out by hand rather than shuffled, to start a game from a given
+//! position when teaching or reproducing a bug.
+
+use std::{collections::HashSet, fmt};
+
+use serde::{Deserialize, Serialize};
+use shuftlib::{
+    tressette::TressetteCard,
+    trick_taking::{PLAYERS, PlayerId},
+};
+
+use super::record::{card_code, parse_card};
+
+/// Number of cards each player is dealt, the whole deck being dealt.
+pub const HAND_SIZE: usize = 10;
+
+/// The cards dealt to each of the four players and who leads the first
+/// trick. Every card of the 40 cards deck is dealt exactly once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "DealCodes", try_from = "DealCodes")]
+pub struct Deal {
+    hands: [Vec<TressetteCard>; PLAYERS],
+    leader: PlayerId,
+}
+
+/// A deal that doesn't make a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DealError {
+    /// There isn't a hand for each player.
+    Players(usize),
+    /// A card code that doesn't stand for a card.
+    UnknownCard(String),
+    /// A player isn't dealt the right number of cards.
+    HandSize { player: PlayerId, cards: usize },
+    /// The same card is dealt twice.
+    Repeated(TressetteCard),
+    /// The seat leading the first trick doesn't exist.
+    Leader(usize),
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealError::Players(hands) => write!(f, "{hands} hands dealt instead of {PLAYERS}"),
+            DealError::UnknownCard(code) => write!(f, "unknown card {code}"),
+            DealError::HandSize { player, cards } => write!(
+                f,
+                "player {player} is dealt {cards} cards instead of {HAND_SIZE}"
+            ),
+            DealError::Repeated(card) => write!(f, "{} is dealt twice", card_code(*card)),
+            DealError::Leader(seat) => write!(f, "there's no seat {seat} to lead"),
+        }
+    }
+}
+
+impl Deal {
+    /// Checks that `hands` deal the whole deck, [`HAND_SIZE`] cards each.
+    pub fn new(hands: [Vec<TressetteCard>; PLAYERS], leader: PlayerId) -> Result<Self, DealError> {
+        let mut dealt = HashSet::new();
+        for (player, hand) in hands.iter().enumerate() {
+            if hand.len() != HAND_SIZE {
+                return Err(DealError::HandSize {
+                    player: PlayerId::try_from(player).unwrap(),
+                    cards: hand.len(),
+                });
+            }
+            if let Some(card) = hand.iter().find(|card| !dealt.insert(**card)) {
+                return Err(DealError::Repeated(*card));
+            }
+        }
+        Ok(Self { hands, leader })
+    }
+
+    /// Reads a deal from the codes of the cards in each hand, separated by
+    /// spaces, and the seat of the leader.
+    pub fn from_codes(hands: &[&str], leader: usize) -> Result<Self, DealError> {
+        let hands = hands
+            .iter()
+            .map(|hand| {
+                hand.split_whitespace()
+                    .map(|code| parse_card(code).ok_or_else(|| DealError::UnknownCard(code.into())))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let players = hands.len();
+        let hands = hands.try_into().map_err(|_| DealError::Players(players))?;
+        let leader = PlayerId::try_from(leader).map_err(|_| DealError::Leader(leader))?;
+        Self::new(hands, leader)
+    }
+
+    /// Cards dealt to each player, by seat.
+    pub fn hands(&self) -> &[Vec<TressetteCard>; PLAYERS] {
+        &self.hands
+    }
+
+    /// Codes of the cards dealt to each player, as read by
+    /// [`Deal::from_codes`].
+    pub fn hand_codes(&self) -> Vec<String> {
+        self.hands
+            .iter()
+            .map(|hand| {
+                hand.iter()
+                    .map(|card| card_code(*card))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    /// Player leading the first trick.
+    pub fn leader(&self) -> PlayerId {
+        self.leader
+    }
+}
+
+/// How a deal is written in files: each hand as the codes of its cards, and
+/// the seat of the leader, 0 being the POV player.
+#[derive(Serialize, Deserialize)]
+struct DealCodes {
+    hands: Vec<String>,
+    leader: usize,
+}
+
+impl From<Deal> for DealCodes {
+    fn from(deal: Deal) -> Self {
+        Self {
+            hands: deal.hand_codes(),
+            leader: deal.leader.as_usize(),
+        }
+    }
+}
+
+impl TryFrom<DealCodes> for Deal {
+    type Error = DealError;
+
+    fn try_from(codes: DealCodes) -> Result<Self, Self::Error> {
+        let hands: Vec<&str> = codes.hands.iter().map(String::as_str).collect();
+        Self::from_codes(&hands, codes.leader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::fixtures::suit_per_player;
+
+    /// Every card of the deck, a suit to each player.
+    fn suit_hands() -> Vec<String> {
+        suit_per_player()
+            .iter()
+            .map(|hand| {
+                hand.iter()
+                    .map(|card| card_code(*card))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    fn from_hands(hands: &[String], leader: usize) -> Result<Deal, DealError> {
+        let hands: Vec<&str> = hands.iter().map(String::as_str).collect();
+        Deal::from_codes(&hands, leader)
+    }
+
+    #[test]
+    fn whole_deck_is_a_deal() {
+        let hands = suit_hands();
+        let deal = from_hands(&hands, 2).unwrap();
+        assert_eq!(deal.hand_codes(), hands);
+        assert_eq!(deal.leader().as_usize(), 2);
+    }
+
+    #[test]
+    fn one_hand_per_player() {
+        let hands = suit_hands();
+        assert_eq!(from_hands(&hands[..3], 0), Err(DealError::Players(3)));
+    }
+
+    #[test]
+    fn unknown_card() {
+        let mut hands = suit_hands();
+        hands[1] = hands[1].replacen("1", "11", 1);
+        assert_eq!(
+            from_hands(&hands, 0),
+            Err(DealError::UnknownCard(hands[1][..3].into()))
+        );
+    }
+
+    #[test]
+    fn hand_size() {
+        let mut hands = suit_hands();
+        let last = hands[3].rfind(' ').unwrap();
+        let moved = hands[3].split_off(last);
+        hands[2].push_str(&moved);
+        assert_eq!(
+            from_hands(&hands, 0),
+            Err(DealError::HandSize {
+                player: PlayerId::try_from(2).unwrap(),
+                cards: HAND_SIZE + 1,
+            })
+        );
+    }
+
+    #[test]
+    fn repeated_card() {
+        let mut hands = suit_hands();
+        let (last, first) = (hands[0].rfind(' ').unwrap(), hands[1].find(' ').unwrap());
+        let card = hands[1][..first].to_string();
+        hands[0].replace_range(last + 1.., &card);
+        assert_eq!(
+            from_hands(&hands, 0),
+            Err(DealError::Repeated(parse_card(&card).unwrap()))
+        );
+    }
+
+    #[test]
+    fn leader_seat() {
+        assert_eq!(
+            from_hands(&suit_hands(), PLAYERS),
+            Err(DealError::Leader(PLAYERS))
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Cards and deals shared by the tests of the engines.
+
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::TressetteCard,
+    trick_taking::PLAYERS,
+};
+
+/// A whole suit to each of four players, in the order Hearts, Diamonds,
+/// Clubs and Spades.
+pub(crate) fn suit_per_player() -> [Vec<TressetteCard>; PLAYERS] {
+    [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades].map(|suit| {
+        (1..=10)
+            .map(|rank| TressetteCard::new(ItalianRank::from_repr(rank).unwrap(), suit))
+            .collect()
+    })
+}
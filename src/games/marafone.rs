@@ -0,0 +1,551 @@
+//! Marafone (also known as Beccaccino), the trump Tressette of Romagna.
+//!
+//! Four players in two teams get 10 cards each. Before the first trick the
+//! leader chooses the trump suit ("briscola"): the holder of the Four of
+//! Coins in the first hand, then each player in turn. Players must follow
+//! suit when they can; the highest trump takes the trick, or the highest
+//! card of the led suit if no trump was played.
+//!
+//! Cards are worth what they're worth in Tressette and the last trick is
+//! worth an extra point. The player choosing trump while holding its Ace, Two
+//! and Three may declare the "maraffa", worth 3 more points. The first team
+//! to reach 41 points wins.
+
+use rand::{SeedableRng, rngs::StdRng};
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::{Error, Status, TressetteCard},
+    trick_taking::PlayerId,
+};
+
+use super::{
+    CardGame, MoveEffect, PendingPhase, SummaryLine, card_lines,
+    deal::Deal,
+    record::{InvalidMove, Move},
+    shuffled_deck, thirds, took_every_trick,
+};
+
+/// Number of seats at the table.
+pub const SEATS: usize = 4;
+
+/// Number of cards dealt to each player at the start of a hand.
+const HAND_SIZE: usize = 10;
+
+/// Suits trump can be chosen from.
+const SUITS: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+/// Points a team needs to win the game.
+const POINTS_TO_WIN: u8 = 41;
+
+/// Points the maraffa is worth.
+const MARAFFA_POINTS: u8 = 3;
+
+/// An error that can occur while choosing trump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrumpError {
+    /// Trump has already been chosen for this hand.
+    AlreadyChosen,
+    /// The maraffa was declared without holding it.
+    NoMaraffa(Suit),
+}
+
+#[derive(Debug)]
+pub struct Marafone {
+    rng: StdRng,
+    hands: [Vec<TressetteCard>; SEATS],
+    trump: Option<Suit>,
+    maraffa: Option<PlayerId>,
+    trick: [Option<TressetteCard>; SEATS],
+    leader: PlayerId,
+    /// The player choosing trump this hand.
+    chooser: PlayerId,
+    captured: [Vec<TressetteCard>; 2],
+    score: [u8; 2],
+    history: Vec<(TressetteCard, MoveEffect)>,
+    /// Points each team made in the last hand completed.
+    hand_points: [u8; 2],
+    /// Team that took every trick of the last hand completed, if any.
+    cappotto: Option<usize>,
+    /// What each team made in the last hand completed.
+    summary: Vec<SummaryLine>,
+}
+
+impl Marafone {
+    /// Creates a new game with the first hand dealt, waiting for trump to be
+    /// chosen by whoever holds the Four of Coins. Games created from the same
+    /// `seed` deal the same cards.
+    pub fn new(seed: u64) -> Self {
+        let mut game = Self::seated(StdRng::seed_from_u64(seed));
+        game.deal();
+        let four_of_coins = TressetteCard::new(ItalianRank::Four, Suit::Diamonds);
+        game.chooser = (0..SEATS)
+            .map(seat)
+            .find(|p| game.hands[p.as_usize()].contains(&four_of_coins))
+            .unwrap_or(PlayerId::PLAYER_0);
+        game.leader = game.chooser;
+        game
+    }
+
+    /// Creates a new game with the first hand dealt as in `deal`, its leader
+    /// choosing trump. The hands after it are shuffled from `seed`.
+    pub fn from_deal(deal: &Deal, seed: u64) -> Self {
+        Self {
+            hands: deal.hands().clone(),
+            leader: deal.leader(),
+            chooser: deal.leader(),
+            ..Self::seated(StdRng::seed_from_u64(seed))
+        }
+    }
+
+    /// A game with no card dealt yet.
+    fn seated(rng: StdRng) -> Self {
+        Self {
+            rng,
+            hands: Default::default(),
+            trump: None,
+            maraffa: None,
+            trick: [None; SEATS],
+            leader: PlayerId::PLAYER_0,
+            chooser: PlayerId::PLAYER_0,
+            captured: Default::default(),
+            score: [0; 2],
+            history: Vec::new(),
+            hand_points: [0; 2],
+            cappotto: None,
+            summary: Vec::new(),
+        }
+    }
+
+    /// Returns the trump suit, once chosen.
+    pub fn trump(&self) -> Option<Suit> {
+        self.trump
+    }
+
+    /// Returns the player who declared the maraffa this hand, if any.
+    pub fn maraffa(&self) -> Option<PlayerId> {
+        self.maraffa
+    }
+
+    /// Returns the player whose turn it is: the one choosing trump, then the
+    /// next to play.
+    pub fn current_player(&self) -> PlayerId {
+        if self.trump.is_none() {
+            return self.chooser;
+        }
+        let played = self.trick.iter().flatten().count();
+        seat(self.leader.as_usize() + played)
+    }
+
+    /// Returns the score of each team.
+    pub fn score(&self) -> [u8; 2] {
+        self.score
+    }
+
+    /// Returns the current status of the game.
+    pub fn status(&self) -> Status {
+        let [first, second] = self.score;
+        if first.max(second) >= POINTS_TO_WIN && first != second {
+            Status::Finished {
+                winner: if first > second { 0 } else { 1 },
+            }
+        } else {
+            Status::Ongoing
+        }
+    }
+
+    /// Returns the cards in the given player's hand.
+    pub fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        &self.hands[player.as_usize()]
+    }
+
+    /// Returns the cards played in the ongoing trick, indexed by player.
+    pub fn current_trick(&self) -> &[Option<TressetteCard>; SEATS] {
+        &self.trick
+    }
+
+    /// Returns the player who led the current trick.
+    pub fn trick_leader(&self) -> PlayerId {
+        self.leader
+    }
+
+    /// Returns the card history.
+    pub fn history(&self) -> &[(TressetteCard, MoveEffect)] {
+        &self.history
+    }
+
+    /// Whether the player choosing trump holds the maraffa in `suit`.
+    pub fn has_maraffa(&self, suit: Suit) -> bool {
+        let hand = &self.hands[self.chooser.as_usize()];
+        [ItalianRank::Ace, ItalianRank::Two, ItalianRank::Three]
+            .iter()
+            .all(|rank| hand.contains(&TressetteCard::new(*rank, suit)))
+    }
+
+    /// Chooses the trump suit for the hand, declaring the maraffa if asked.
+    pub fn choose_trump(&mut self, suit: Suit, declare_maraffa: bool) -> Result<(), TrumpError> {
+        if self.trump.is_some() {
+            return Err(TrumpError::AlreadyChosen);
+        }
+        if declare_maraffa && !self.has_maraffa(suit) {
+            return Err(TrumpError::NoMaraffa(suit));
+        }
+        self.trump = Some(suit);
+        self.maraffa = declare_maraffa.then_some(self.chooser);
+        Ok(())
+    }
+
+    /// Trump a bot would choose: the suit it holds most of, declaring the
+    /// maraffa whenever it has one.
+    pub fn bot_trump(&self) -> (Suit, bool) {
+        let hand = &self.hands[self.chooser.as_usize()];
+        let suit = SUITS
+            .into_iter()
+            .max_by_key(|suit| {
+                let cards = hand.iter().filter(|c| c.suit() == *suit);
+                (self.has_maraffa(*suit), cards.clone().count(), cards.max())
+            })
+            .unwrap_or(Suit::Hearts);
+        (suit, self.has_maraffa(suit))
+    }
+
+    /// Returns all legal cards for the current player, none until trump is
+    /// chosen.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if self.trump.is_none() || matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+
+        let hand = &self.hands[self.current_player().as_usize()];
+        match self.trick[self.leader.as_usize()] {
+            Some(led) if hand.iter().any(|c| c.suit() == led.suit()) => hand
+                .iter()
+                .filter(|c| c.suit() == led.suit())
+                .copied()
+                .collect(),
+            _ => hand.clone(),
+        }
+    }
+
+    /// Plays a card for the current player.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+        if self.trump.is_none() {
+            return Err(Error::InternalError(
+                "Cannot play a card before trump is chosen".to_string(),
+            ));
+        }
+
+        let player = self.current_player();
+        if !self.legal_cards().contains(&card) {
+            if !self.hand(player).contains(&card) {
+                return Err(Error::CardNotInHand { card, player });
+            }
+            let required_suit = self.trick[self.leader.as_usize()]
+                .map(|c| c.suit())
+                .ok_or(Error::InternalError("No leading suit".to_string()))?;
+            return Err(Error::MustFollowSuit {
+                suit: card.suit(),
+                required_suit,
+            });
+        }
+
+        self.hands[player.as_usize()].retain(|&c| c != card);
+        self.trick[player.as_usize()] = Some(card);
+
+        let effect = if self.trick.iter().all(Option::is_some) {
+            self.complete_trick()
+        } else {
+            MoveEffect::CardPlayed
+        };
+        self.history.push((card, effect));
+
+        Ok(effect)
+    }
+
+    fn complete_trick(&mut self) -> MoveEffect {
+        let led = self.trick[self.leader.as_usize()].map(|c| c.suit());
+        let trump = self.trump;
+        // Trumps beat every other suit, then the led suit beats the rest.
+        let winner = self
+            .trick
+            .iter()
+            .enumerate()
+            .filter_map(|(player, card)| card.map(|c| (player, c)))
+            .filter(|(_, card)| Some(card.suit()) == trump || Some(card.suit()) == led)
+            .max_by_key(|(_, card)| (Some(card.suit()) == trump, *card))
+            .map_or(self.leader, |(player, _)| seat(player));
+
+        self.captured[winner.as_usize() % 2].extend(self.trick.iter().flatten());
+        self.trick = [None; SEATS];
+        self.leader = winner;
+
+        if self.hands.iter().all(Vec::is_empty) {
+            self.complete_hand(winner)
+        } else {
+            MoveEffect::TrickCompleted { winner }
+        }
+    }
+
+    fn complete_hand(&mut self, last_trick_winner: PlayerId) -> MoveEffect {
+        let mut points = self
+            .captured
+            .each_ref()
+            .map(|cards| cards.iter().map(|c| thirds(*c)).sum::<u8>() / 3);
+        let mut last_trick = [0; 2];
+        last_trick[last_trick_winner.as_usize() % 2] = 1;
+        let mut maraffa = [0; 2];
+        if let Some(player) = self.maraffa {
+            maraffa[player.as_usize() % 2] = MARAFFA_POINTS;
+        }
+        for team in 0..2 {
+            points[team] += last_trick[team] + maraffa[team];
+        }
+        self.score[0] += points[0];
+        self.score[1] += points[1];
+        self.hand_points = points;
+        self.cappotto = took_every_trick(&self.captured);
+
+        self.summary = card_lines(&self.captured);
+        self.summary.extend([
+            SummaryLine::new("Last trick", &last_trick),
+            SummaryLine::new("Maraffa", &maraffa),
+            SummaryLine::new("Hand", &points),
+            SummaryLine::new("Total", &self.score),
+        ]);
+
+        if matches!(self.status(), Status::Finished { .. }) {
+            MoveEffect::GameOver {
+                trick_winner: last_trick_winner,
+            }
+        } else {
+            self.chooser = seat(self.chooser.as_usize() + 1);
+            self.leader = self.chooser;
+            self.deal();
+            MoveEffect::HandComplete {
+                trick_winner: last_trick_winner,
+            }
+        }
+    }
+
+    /// Shuffles a full deck and deals every hand, leaving trump to be chosen.
+    fn deal(&mut self) {
+        let mut deck = shuffled_deck(&mut self.rng);
+
+        self.hands = Default::default();
+        self.captured = Default::default();
+        for hand in self.hands.iter_mut() {
+            if let Some(cards) = deck.draw_n(HAND_SIZE) {
+                hand.extend(cards);
+            }
+        }
+        self.trump = None;
+        self.maraffa = None;
+        self.trick = [None; SEATS];
+    }
+}
+
+impl Default for Marafone {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl CardGame for Marafone {
+    fn seats(&self) -> usize {
+        SEATS
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        Marafone::hand(self, player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        Marafone::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        Marafone::current_trick(self)
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        Marafone::trick_leader(self)
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        Marafone::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        Marafone::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.history().last().map(|(_, effect)| *effect)
+    }
+
+    fn score(&self) -> Vec<u8> {
+        Marafone::score(self).to_vec()
+    }
+
+    fn status(&self) -> Status {
+        Marafone::status(self)
+    }
+
+    fn pending_phase(&self) -> Option<PendingPhase> {
+        self.trump.is_none().then_some(PendingPhase::Trump)
+    }
+
+    fn pending_moves(&self) -> Vec<Move> {
+        if self.trump.is_some() {
+            return Vec::new();
+        }
+        // Whoever holds the maraffa in a suit declares it with the trump.
+        SUITS
+            .into_iter()
+            .map(|suit| Move::Trump {
+                suit,
+                maraffa: self.has_maraffa(suit),
+            })
+            .collect()
+    }
+
+    fn bot_move(&self) -> Option<Move> {
+        self.trump.is_none().then(|| {
+            let (suit, maraffa) = self.bot_trump();
+            Move::Trump { suit, maraffa }
+        })
+    }
+
+    fn apply(&mut self, mv: &Move) -> Result<(), InvalidMove> {
+        let made = match mv {
+            Move::Play(card) => self.play_card(*card).is_ok(),
+            Move::Trump { suit, maraffa } => self.choose_trump(*suit, *maraffa).is_ok(),
+            _ => false,
+        };
+        made.then_some(()).ok_or_else(|| InvalidMove(mv.clone()))
+    }
+
+    fn trump_suit(&self) -> Option<Suit> {
+        self.trump
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn cappotto(&self) -> Option<usize> {
+        self.cappotto
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+}
+
+/// Returns the player sitting at `index`, wrapping around the table.
+fn seat(index: usize) -> PlayerId {
+    PlayerId::try_from(index % SEATS).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::fixtures::suit_per_player;
+
+    /// A game where each player is dealt `hands`, trump being still to be
+    /// chosen by the first seat, who also leads the first trick.
+    fn game_with(hands: [Vec<TressetteCard>; SEATS]) -> Marafone {
+        Marafone::from_deal(&Deal::new(hands, PlayerId::PLAYER_0).unwrap(), 0)
+    }
+
+    /// Swaps `a` and `b` between the hands holding them.
+    fn swap(hands: &mut [Vec<TressetteCard>; SEATS], a: TressetteCard, b: TressetteCard) {
+        for hand in hands.iter_mut() {
+            for card in hand.iter_mut() {
+                if *card == a {
+                    *card = b;
+                } else if *card == b {
+                    *card = a;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn trump_is_chosen_once_before_the_play() {
+        let mut game = game_with(suit_per_player());
+        assert_eq!(game.pending_phase(), Some(PendingPhase::Trump));
+        assert!(game.legal_cards().is_empty());
+        assert_eq!(
+            game.choose_trump(Suit::Spades, true),
+            Err(TrumpError::NoMaraffa(Suit::Spades))
+        );
+
+        game.choose_trump(Suit::Spades, false).unwrap();
+        assert_eq!(game.trump(), Some(Suit::Spades));
+        assert_eq!(game.maraffa(), None);
+        assert_eq!(game.pending_phase(), None);
+        assert_eq!(
+            game.choose_trump(Suit::Hearts, true),
+            Err(TrumpError::AlreadyChosen)
+        );
+    }
+
+    #[test]
+    fn maraffa_is_worth_three_more_points() {
+        let mut game = game_with(suit_per_player());
+        assert!(game.has_maraffa(Suit::Hearts));
+        game.choose_trump(Suit::Hearts, true).unwrap();
+        assert_eq!(game.maraffa(), Some(PlayerId::PLAYER_0));
+
+        while game.score() == [0, 0] {
+            game.play_card(game.legal_cards()[0]).unwrap();
+        }
+        // Player 0 trumps every trick: 10 points from the cards, one for the
+        // last trick and three for the maraffa.
+        assert_eq!(game.score(), [14, 0]);
+    }
+
+    #[test]
+    fn any_trump_beats_the_led_suit() {
+        let mut hands = suit_per_player();
+        let card = TressetteCard::new;
+        // Players 1 and 3 have a Heart to follow with, player 2 only a trump.
+        swap(
+            &mut hands,
+            card(ItalianRank::Three, Suit::Hearts),
+            card(ItalianRank::Three, Suit::Diamonds),
+        );
+        swap(
+            &mut hands,
+            card(ItalianRank::Two, Suit::Hearts),
+            card(ItalianRank::Two, Suit::Spades),
+        );
+        swap(
+            &mut hands,
+            card(ItalianRank::Four, Suit::Clubs),
+            card(ItalianRank::Four, Suit::Spades),
+        );
+        let mut game = game_with(hands);
+        game.choose_trump(Suit::Spades, false).unwrap();
+
+        game.play_card(card(ItalianRank::King, Suit::Hearts))
+            .unwrap();
+        game.play_card(card(ItalianRank::Three, Suit::Hearts))
+            .unwrap();
+        game.play_card(card(ItalianRank::Four, Suit::Spades))
+            .unwrap();
+        let effect = game
+            .play_card(card(ItalianRank::Two, Suit::Hearts))
+            .unwrap();
+        assert_eq!(
+            effect,
+            MoveEffect::TrickCompleted {
+                winner: PlayerId::try_from(2).unwrap()
+            }
+        );
+        assert_eq!(game.trick_leader(), PlayerId::try_from(2).unwrap());
+    }
+}
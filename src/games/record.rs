@@ -0,0 +1,186 @@
+//! Moves made during a game, written as short text so that a game can be
+//! saved and played again from its seed.
+//!
+//! Cards are written with their rank, from 1 for the Ace to 10 for the King,
+//! followed by the initial of their Italian suit: C for Cups ("coppe"), D
+//! for Coins ("denari"), B for Clubs ("bastoni") and S for Swords ("spade").
+//! The Seven of Coins is `7D`.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::TressetteCard,
+};
+
+use super::{accusi::Accuso, terziglio::Bid};
+
+/// Something a player does at the table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Move {
+    /// A card played to the trick.
+    Play(TressetteCard),
+    /// A card played capturing some cards from the table.
+    Capture(TressetteCard, Vec<TressetteCard>),
+    /// Trump chosen for the hand, with or without declaring the maraffa.
+    Trump { suit: Suit, maraffa: bool },
+    /// An accuso declared before the first card of the hand.
+    Declare(Accuso),
+    /// A bid during the auction.
+    Bid(Bid),
+    /// The Three asked for by the caller.
+    CallThree(TressetteCard),
+    /// The card the caller gives back for the Three.
+    GiveBack(TressetteCard),
+    /// The cards the caller discards after taking the monte.
+    Discard(Vec<TressetteCard>),
+}
+
+/// A move that can't be made where the game is at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMove(pub Move);
+
+const SUITS: [(Suit, char); 4] = [
+    (Suit::Hearts, 'C'),
+    (Suit::Diamonds, 'D'),
+    (Suit::Clubs, 'B'),
+    (Suit::Spades, 'S'),
+];
+
+fn suit_code(suit: Suit) -> char {
+    SUITS
+        .iter()
+        .find(|(s, _)| *s == suit)
+        .map_or('?', |(_, code)| *code)
+}
+
+fn parse_suit(code: &str) -> Option<Suit> {
+    let mut chars = code.chars();
+    let (Some(code), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    SUITS
+        .iter()
+        .find(|(_, c)| *c == code)
+        .map(|(suit, _)| *suit)
+}
+
+/// Code of a card, e.g. `7D` for the Seven of Coins.
+pub fn card_code(card: TressetteCard) -> String {
+    format!("{}{}", card.rank() as u8, suit_code(card.suit()))
+}
+
+/// Reads a card from its code, as written by [`card_code`].
+pub fn parse_card(code: &str) -> Option<TressetteCard> {
+    let split = code.len().checked_sub(1)?;
+    let (rank, suit) = (code.get(..split)?, code.get(split..)?);
+    let rank = ItalianRank::from_repr(rank.parse().ok()?)?;
+    Some(TressetteCard::new(rank, parse_suit(suit)?))
+}
+
+fn cards_code(cards: &[TressetteCard]) -> String {
+    cards
+        .iter()
+        .map(|card| card_code(*card))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Play(card) => write!(f, "play {}", card_code(*card)),
+            Move::Capture(card, capture) => {
+                write!(f, "capture {} {}", card_code(*card), cards_code(capture))
+            }
+            Move::Trump { suit, maraffa } => {
+                write!(f, "trump {}", suit_code(*suit))?;
+                if *maraffa {
+                    write!(f, " maraffa")?;
+                }
+                Ok(())
+            }
+            Move::Declare(Accuso::Napoletana(suit)) => {
+                write!(f, "declare napoletana {}", suit_code(*suit))
+            }
+            Move::Declare(Accuso::Buongioco { rank, count }) => {
+                write!(f, "declare buongioco {} {count}", *rank as u8)
+            }
+            Move::Bid(Bid::Pass) => write!(f, "bid pass"),
+            Move::Bid(Bid::Call) => write!(f, "bid call"),
+            Move::CallThree(three) => write!(f, "call {}", card_code(*three)),
+            Move::GiveBack(card) => write!(f, "give {}", card_code(*card)),
+            Move::Discard(cards) => write!(f, "discard {}", cards_code(cards)),
+        }
+    }
+}
+
+/// A move that couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError(pub String);
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid move: {}", self.0)
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseMoveError(s.to_string());
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let card = |word: &str| parse_card(word).ok_or_else(error);
+        let cards = |words: &[&str]| -> Result<Vec<TressetteCard>, ParseMoveError> {
+            words.iter().map(|word| card(word)).collect()
+        };
+        match words.as_slice() {
+            ["play", played] => Ok(Move::Play(card(played)?)),
+            ["capture", played, captured @ ..] => {
+                Ok(Move::Capture(card(played)?, cards(captured)?))
+            }
+            ["trump", suit] => Ok(Move::Trump {
+                suit: parse_suit(suit).ok_or_else(error)?,
+                maraffa: false,
+            }),
+            ["trump", suit, "maraffa"] => Ok(Move::Trump {
+                suit: parse_suit(suit).ok_or_else(error)?,
+                maraffa: true,
+            }),
+            ["declare", "napoletana", suit] => Ok(Move::Declare(Accuso::Napoletana(
+                parse_suit(suit).ok_or_else(error)?,
+            ))),
+            ["declare", "buongioco", rank, count] => Ok(Move::Declare(Accuso::Buongioco {
+                rank: rank
+                    .parse()
+                    .ok()
+                    .and_then(ItalianRank::from_repr)
+                    .ok_or_else(error)?,
+                count: count.parse().map_err(|_| error())?,
+            })),
+            ["bid", "pass"] => Ok(Move::Bid(Bid::Pass)),
+            ["bid", "call"] => Ok(Move::Bid(Bid::Call)),
+            ["call", three] => Ok(Move::CallThree(card(three)?)),
+            ["give", given] => Ok(Move::GiveBack(card(given)?)),
+            ["discard", discarded @ ..] => Ok(Move::Discard(cards(discarded)?)),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl From<Move> for String {
+    fn from(mv: Move) -> Self {
+        mv.to_string()
+    }
+}
+
+impl TryFrom<String> for Move {
+    type Error = ParseMoveError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
@@ -0,0 +1,299 @@
+//! Tressette "a perdere", also known as Rovescino or Ciapanò.
+//!
+//! Four players each play for themselves, following suit as in Tressette,
+//! and try to take as few points as possible. Cards are worth what they're
+//! worth in Tressette and the last trick is worth an extra point. A player
+//! taking every card worth something makes "cappotto": they score nothing
+//! and everybody else scores 11 instead.
+//!
+//! The game ends when somebody reaches 21 points, and whoever has the fewest
+//! wins.
+
+use rand::{SeedableRng, rngs::StdRng};
+use shuftlib::{
+    tressette::{Error, Game, Status, TressetteCard},
+    trick_taking::{PLAYERS, PlayerId},
+};
+
+use super::{
+    CardGame, MoveEffect, SummaryLine, deal::Deal, deal_tressette, seat_before, thirds,
+    tressette_from_deal,
+};
+
+/// Points ending the game once a player reaches them.
+const POINTS_TO_END: u8 = 21;
+
+/// Points each other player scores when somebody makes cappotto.
+const CAPPOTTO_POINTS: u8 = 11;
+
+/// Thirds of a point all the cards are worth together: a point for each Ace
+/// and a third for each Two, Three and face card.
+const ALL_THIRDS: u8 = 32;
+
+#[derive(Debug)]
+pub struct Rovescino {
+    rng: StdRng,
+    game: Game,
+    /// Player who dealt the hand being played.
+    dealer: PlayerId,
+    /// Thirds of a point taken in the ongoing hand, by player.
+    taken: [u8; PLAYERS],
+    score: [u8; PLAYERS],
+    /// Player who made cappotto in the last completed hand, if any.
+    cappotto: Option<PlayerId>,
+    summary: Vec<SummaryLine>,
+    last_effect: Option<MoveEffect>,
+    /// Points each player scored in the last hand completed.
+    hand_points: [u8; PLAYERS],
+}
+
+impl Rovescino {
+    /// Creates a new game with the first hand already dealt. Games created
+    /// from the same `seed` deal the same cards.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::dealt(deal_tressette(&mut rng), rng)
+    }
+
+    /// Creates a new game with the first hand dealt as in `deal`. The hands
+    /// after it are shuffled from `seed`.
+    pub fn from_deal(deal: &Deal, seed: u64) -> Self {
+        Self::dealt(tressette_from_deal(deal), StdRng::seed_from_u64(seed))
+    }
+
+    fn dealt(game: Game, rng: StdRng) -> Self {
+        Self {
+            dealer: seat_before(game.trick_leader()),
+            game,
+            rng,
+            taken: [0; PLAYERS],
+            score: [0; PLAYERS],
+            cappotto: None,
+            summary: Vec::new(),
+            last_effect: None,
+            hand_points: [0; PLAYERS],
+        }
+    }
+
+    /// Returns the score of each player.
+    pub fn score(&self) -> [u8; PLAYERS] {
+        self.score
+    }
+
+    /// Returns the player who made cappotto in the last completed hand.
+    pub fn cappotto(&self) -> Option<PlayerId> {
+        self.cappotto
+    }
+
+    /// Returns the current status of the game: once somebody reaches the end,
+    /// the player with the fewest points wins, unless they're tied.
+    pub fn status(&self) -> Status {
+        if self.score.iter().all(|points| *points < POINTS_TO_END) {
+            return Status::Ongoing;
+        }
+        let Some(lowest) = self.score.iter().min() else {
+            return Status::Ongoing;
+        };
+        let mut lowest_players = (0..PLAYERS).filter(|p| self.score[*p] == *lowest);
+        match (lowest_players.next(), lowest_players.next()) {
+            (Some(winner), None) => Status::Finished {
+                winner: winner as u8,
+            },
+            _ => Status::Ongoing,
+        }
+    }
+
+    /// Returns all legal cards for the current player.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+        self.game.legal_cards()
+    }
+
+    /// Plays a card for the current player.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+
+        let mut trick: Vec<TressetteCard> = self
+            .game
+            .current_trick()
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        trick.push(card);
+        let mut effect = MoveEffect::from(self.game.play_card(card)?);
+        match effect {
+            MoveEffect::CardPlayed => {}
+            MoveEffect::TrickCompleted { winner } => self.take(winner, &trick),
+            MoveEffect::HandComplete { trick_winner } | MoveEffect::GameOver { trick_winner } => {
+                self.take(trick_winner, &trick);
+                effect = self.complete_hand(trick_winner);
+            }
+        }
+        self.last_effect = Some(effect);
+
+        Ok(effect)
+    }
+
+    fn take(&mut self, winner: PlayerId, trick: &[TressetteCard]) {
+        self.taken[winner.as_usize()] += trick.iter().map(|c| thirds(*c)).sum::<u8>();
+    }
+
+    fn complete_hand(&mut self, last_trick_winner: PlayerId) -> MoveEffect {
+        self.cappotto = (0..PLAYERS)
+            .find(|p| self.taken[*p] == ALL_THIRDS)
+            .map(|p| PlayerId::try_from(p).unwrap());
+
+        self.taken[last_trick_winner.as_usize()] += 3;
+        let taken = self.taken.map(|thirds| thirds / 3);
+        let hand = match self.cappotto {
+            Some(cappotto) => {
+                let mut hand = [CAPPOTTO_POINTS; PLAYERS];
+                hand[cappotto.as_usize()] = 0;
+                hand
+            }
+            None => taken,
+        };
+        for (score, points) in self.score.iter_mut().zip(hand) {
+            *score += points;
+        }
+        self.hand_points = hand;
+        self.taken = [0; PLAYERS];
+
+        let hand_label = if self.cappotto.is_some() {
+            "Hand (cappotto)"
+        } else {
+            "Hand"
+        };
+        self.summary = [("Taken", taken), (hand_label, hand), ("Total", self.score)]
+            .into_iter()
+            .map(|(label, points)| SummaryLine {
+                label: label.to_string(),
+                points: points.to_vec(),
+            })
+            .collect();
+
+        if matches!(self.status(), Status::Finished { .. }) {
+            return MoveEffect::GameOver {
+                trick_winner: last_trick_winner,
+            };
+        }
+        // shuftlib stops dealing once a team reaches its target: start over
+        // with a fresh deal, the score being kept here.
+        if matches!(self.game.status(), Status::Finished { .. }) {
+            self.game = deal_tressette(&mut self.rng);
+        }
+        self.dealer = seat_before(self.game.trick_leader());
+        MoveEffect::HandComplete {
+            trick_winner: last_trick_winner,
+        }
+    }
+}
+
+impl Default for Rovescino {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl CardGame for Rovescino {
+    fn seats(&self) -> usize {
+        PLAYERS
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        self.game.hand(player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        self.game.current_player()
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        self.game.current_trick()
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        self.game.trick_leader()
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        Rovescino::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        Rovescino::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.last_effect
+    }
+
+    fn score(&self) -> Vec<u8> {
+        Rovescino::score(self).to_vec()
+    }
+
+    fn status(&self) -> Status {
+        Rovescino::status(self)
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn cappotto(&self) -> Option<usize> {
+        Rovescino::cappotto(self).map(|player| player.as_usize())
+    }
+
+    fn dealer(&self) -> Option<PlayerId> {
+        Some(self.dealer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(index: usize) -> PlayerId {
+        PlayerId::try_from(index).unwrap()
+    }
+
+    #[test]
+    fn each_player_scores_the_points_they_took() {
+        let mut game = Rovescino::new(0);
+        game.taken = [9, 12, 6, 5];
+        game.complete_hand(player(2));
+        // The last trick is worth a point to whoever took it.
+        assert_eq!(game.score(), [3, 4, 3, 1]);
+        assert_eq!(game.cappotto(), None);
+    }
+
+    #[test]
+    fn cappotto_scores_for_everybody_else() {
+        let mut game = Rovescino::new(0);
+        game.taken = [0, ALL_THIRDS, 0, 0];
+        game.complete_hand(player(1));
+        assert_eq!(game.cappotto(), Some(player(1)));
+        assert_eq!(game.score(), [11, 0, 11, 11]);
+    }
+
+    #[test]
+    fn fewest_points_win_once_somebody_reaches_the_end() {
+        let mut game = Rovescino::new(0);
+        game.score = [20, 5, 10, 12];
+        assert_eq!(game.status(), Status::Ongoing);
+        game.score = [21, 5, 10, 12];
+        assert_eq!(game.status(), Status::Finished { winner: 1 });
+        // Nobody wins while the lowest score is shared.
+        game.score = [21, 5, 5, 12];
+        assert_eq!(game.status(), Status::Ongoing);
+    }
+}
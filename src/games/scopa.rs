@@ -0,0 +1,782 @@
+//! Scopa, for two players or for four players in two teams.
+//!
+//! Four cards are laid face up on the table and each player gets 3, dealing
+//! 3 more each whenever the hands are empty until the deck runs out. A card
+//! captures a table card of the same rank or, if there's none, table cards
+//! whose ranks add up to its own; otherwise it's left on the table. Clearing
+//! the table is a "scopa", worth a point, unless it happens with the very
+//! last card of the hand. Whatever is left on the table at the end goes to
+//! the last player who captured.
+//!
+//! At the end of the hand a point goes to the side with most cards
+//! ("carte"), most Coins ("denari"), the Seven of Coins ("settebello") and
+//! the best primiera. The first side to reach 11 points wins.
+//!
+//! Scopone Scientifico is played by four players in two teams, each getting
+//! 10 cards with nothing on the table, up to 21 points. The optional
+//! "napola" rule gives a team holding the Ace, Two and Three of Coins a
+//! point for each card of the run from the Ace up.
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::{Error, Status, TressetteCard},
+    trick_taking::PlayerId,
+};
+
+use strum::IntoEnumIterator;
+
+use super::{
+    CardGame, MoveEffect, SummaryLine,
+    record::{InvalidMove, Move},
+    shuffled_deck,
+};
+
+/// How a game of the Scopa family is dealt and scored.
+#[derive(Debug, Clone, Copy)]
+struct Rules {
+    /// Number of cards dealt to each player every time the hands are empty.
+    hand_size: usize,
+    /// Number of cards laid on the table at the start of a hand.
+    table_size: usize,
+    /// Points a side needs to win the game.
+    points_to_win: u8,
+    /// Whether the napola scores.
+    napola: bool,
+}
+
+const SCOPA: Rules = Rules {
+    hand_size: 3,
+    table_size: 4,
+    points_to_win: 11,
+    napola: false,
+};
+
+const SCOPONE: Rules = Rules {
+    hand_size: 10,
+    table_size: 0,
+    points_to_win: 21,
+    napola: false,
+};
+
+/// Suit of the Coins ("denari") in the Italian deck.
+pub const COINS: Suit = Suit::Diamonds;
+
+#[derive(Debug)]
+pub struct Scopa {
+    seats: usize,
+    rng: StdRng,
+    rules: Rules,
+    stock: Vec<TressetteCard>,
+    table: Vec<TressetteCard>,
+    hands: Vec<Vec<TressetteCard>>,
+    captured: [Vec<TressetteCard>; 2],
+    scopas: Vec<(PlayerId, TressetteCard)>,
+    last_capturer: Option<PlayerId>,
+    current: PlayerId,
+    dealer: PlayerId,
+    score: [u8; 2],
+    summary: Vec<SummaryLine>,
+    history: Vec<(TressetteCard, MoveEffect)>,
+    /// Points each side made in the last hand completed.
+    hand_points: [u8; 2],
+}
+
+impl Scopa {
+    /// Creates a new game for `seats` players, 2 or 4, with the first hand
+    /// already dealt. Games created from the same `seed` deal the same cards.
+    pub fn new(seats: usize, seed: u64) -> Self {
+        assert!(
+            seats == 2 || seats == 4,
+            "Scopa is played by 2 or 4 players"
+        );
+        Self::with_rules(seats, SCOPA, seed)
+    }
+
+    /// Creates a new game of Scopone Scientifico, with or without the napola,
+    /// with the first hand already dealt.
+    pub fn scopone(napola: bool, seed: u64) -> Self {
+        Self::with_rules(4, Rules { napola, ..SCOPONE }, seed)
+    }
+
+    /// Creates a new game of Scopa with the first hand dealt as in `hands`
+    /// and `table`, `stock` left to draw from its end and `leader` playing
+    /// first. The hands after it are shuffled from `seed`.
+    pub fn from_deal(
+        hands: Vec<Vec<TressetteCard>>,
+        table: Vec<TressetteCard>,
+        stock: Vec<TressetteCard>,
+        leader: PlayerId,
+        seed: u64,
+    ) -> Self {
+        let seats = hands.len();
+        assert!(
+            seats == 2 || seats == 4,
+            "Scopa is played by 2 or 4 players"
+        );
+        let dealer = seat((leader.as_usize() + seats - 1) % seats);
+        Self {
+            hands,
+            table,
+            stock,
+            current: leader,
+            ..Self::seated(seats, SCOPA, StdRng::seed_from_u64(seed), dealer)
+        }
+    }
+
+    fn with_rules(seats: usize, rules: Rules, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dealer = seat(rng.random_range(0..seats));
+        let mut game = Self::seated(seats, rules, rng, dealer);
+        game.deal();
+        game
+    }
+
+    /// A game for `seats` players with no card dealt yet and `dealer` to
+    /// deal.
+    fn seated(seats: usize, rules: Rules, rng: StdRng, dealer: PlayerId) -> Self {
+        Self {
+            seats,
+            rng,
+            rules,
+            stock: Vec::new(),
+            table: Vec::new(),
+            hands: vec![Vec::new(); seats],
+            captured: Default::default(),
+            scopas: Vec::new(),
+            last_capturer: None,
+            current: dealer,
+            dealer,
+            score: [0; 2],
+            summary: Vec::new(),
+            history: Vec::new(),
+            hand_points: [0; 2],
+        }
+    }
+
+    /// Returns the player whose turn it is to move.
+    pub fn current_player(&self) -> PlayerId {
+        self.current
+    }
+
+    /// Returns the score of each side: of each player with two players, of
+    /// each team with four.
+    pub fn score(&self) -> [u8; 2] {
+        self.score
+    }
+
+    /// Returns the current status of the game.
+    pub fn status(&self) -> Status {
+        let [first, second] = self.score;
+        if first.max(second) >= self.rules.points_to_win && first != second {
+            Status::Finished {
+                winner: if first > second { 0 } else { 1 },
+            }
+        } else {
+            Status::Ongoing
+        }
+    }
+
+    /// Returns the cards in the given player's hand.
+    pub fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        &self.hands[player.as_usize()]
+    }
+
+    /// Returns the cards face up on the table.
+    pub fn table(&self) -> &[TressetteCard] {
+        &self.table
+    }
+
+    /// Returns the scopas made this hand, with the card that made each one.
+    pub fn scopas(&self) -> &[(PlayerId, TressetteCard)] {
+        &self.scopas
+    }
+
+    /// Returns the points each side made in the last completed hand.
+    pub fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+
+    /// Returns the number of cards left to deal.
+    pub fn stock_len(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// Returns all legal cards for the current player: the whole hand.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+        self.hands[self.current.as_usize()].clone()
+    }
+
+    /// Returns every set of table cards `card` could capture. A card of the
+    /// same rank has to be taken over any combination adding up to it.
+    pub fn capture_options(&self, card: TressetteCard) -> Vec<Vec<TressetteCard>> {
+        let same_rank: Vec<Vec<TressetteCard>> = self
+            .table
+            .iter()
+            .filter(|c| c.rank() == card.rank())
+            .map(|c| vec![*c])
+            .collect();
+        if !same_rank.is_empty() {
+            return same_rank;
+        }
+
+        let target = card.rank() as u8;
+        (1..1u32 << self.table.len())
+            .map(|mask| {
+                self.table
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask & (1 << i) != 0)
+                    .map(|(_, c)| *c)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cards| cards.iter().map(|c| c.rank() as u8).sum::<u8>() == target)
+            .collect()
+    }
+
+    /// Plays a card for the current player, taking the most valuable capture
+    /// if there's a choice.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        let capture = self
+            .capture_options(card)
+            .into_iter()
+            .max_by_key(|cards| {
+                (
+                    cards.contains(&settebello()),
+                    cards.len(),
+                    cards.iter().filter(|c| c.suit() == COINS).count(),
+                )
+            })
+            .unwrap_or_default();
+        self.play_capture(card, &capture)
+    }
+
+    /// Plays a card for the current player, capturing `capture` from the
+    /// table. An empty capture leaves the card on the table, which is only
+    /// allowed when it can't capture anything.
+    pub fn play_capture(
+        &mut self,
+        card: TressetteCard,
+        capture: &[TressetteCard],
+    ) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+
+        let player = self.current;
+        if !self.hand(player).contains(&card) {
+            return Err(Error::CardNotInHand { card, player });
+        }
+        let options = self.capture_options(card);
+        let legal = if capture.is_empty() {
+            options.is_empty()
+        } else {
+            options
+                .iter()
+                .any(|o| o.len() == capture.len() && o.iter().all(|c| capture.contains(c)))
+        };
+        if !legal {
+            return Err(Error::InternalError(format!(
+                "{card} can't capture {capture:?}"
+            )));
+        }
+
+        self.hands[player.as_usize()].retain(|&c| c != card);
+        let last_card = self.stock.is_empty() && self.hands.iter().all(Vec::is_empty);
+
+        let captured = !capture.is_empty();
+        if captured {
+            self.table.retain(|c| !capture.contains(c));
+            let side = &mut self.captured[player.as_usize() % 2];
+            side.push(card);
+            side.extend_from_slice(capture);
+            self.last_capturer = Some(player);
+            if self.table.is_empty() && !last_card {
+                self.scopas.push((player, card));
+            }
+        } else {
+            self.table.push(card);
+        }
+        self.current = self.next_by(player, 1);
+
+        let effect = if last_card {
+            self.complete_hand(player)
+        } else {
+            if self.hands.iter().all(Vec::is_empty) {
+                self.deal_hands();
+            }
+            if captured {
+                MoveEffect::TrickCompleted { winner: player }
+            } else {
+                MoveEffect::CardPlayed
+            }
+        };
+        self.history.push((card, effect));
+
+        Ok(effect)
+    }
+
+    /// Returns the card history.
+    pub fn history(&self) -> &[(TressetteCard, MoveEffect)] {
+        &self.history
+    }
+
+    fn complete_hand(&mut self, last_player: PlayerId) -> MoveEffect {
+        let last_capturer = self.last_capturer.unwrap_or(last_player);
+        self.captured[last_capturer.as_usize() % 2].append(&mut self.table);
+
+        let mut lines = hand_points(&self.captured);
+        let mut scopas = [0; 2];
+        for (player, _) in &self.scopas {
+            scopas[player.as_usize() % 2] += 1;
+        }
+        lines.push(("Scopas", scopas));
+        if self.rules.napola {
+            lines.push((
+                "Napola",
+                self.captured.each_ref().map(|cards| napola(cards)),
+            ));
+        }
+
+        let mut hand = [0; 2];
+        for (_, points) in &lines {
+            hand[0] += points[0];
+            hand[1] += points[1];
+        }
+        self.score[0] += hand[0];
+        self.score[1] += hand[1];
+        self.hand_points = hand;
+        lines.push(("Hand", hand));
+        lines.push(("Total", self.score));
+        self.summary = lines
+            .into_iter()
+            .map(|(label, points)| SummaryLine {
+                label: label.to_string(),
+                points: points.to_vec(),
+            })
+            .collect();
+
+        if matches!(self.status(), Status::Finished { .. }) {
+            MoveEffect::GameOver {
+                trick_winner: last_capturer,
+            }
+        } else {
+            self.dealer = self.next_by(self.dealer, 1);
+            self.deal();
+            MoveEffect::HandComplete {
+                trick_winner: last_capturer,
+            }
+        }
+    }
+
+    /// Shuffles a full deck, lays the table and deals the first cards. Three
+    /// or more Kings on the table make for a new deal.
+    fn deal(&mut self) {
+        loop {
+            let deck = shuffled_deck(&mut self.rng);
+            self.stock = deck.into_iter().collect();
+            self.table = self
+                .stock
+                .split_off(self.stock.len() - self.rules.table_size);
+            let kings = self
+                .table
+                .iter()
+                .filter(|c| c.rank() == ItalianRank::King)
+                .count();
+            if kings < 3 {
+                break;
+            }
+        }
+
+        self.captured = Default::default();
+        self.scopas.clear();
+        self.last_capturer = None;
+        self.current = self.next_by(self.dealer, 1);
+        self.deal_hands();
+    }
+
+    /// Deals a new round of cards to every player, starting after the dealer.
+    fn deal_hands(&mut self) {
+        for offset in 1..=self.seats {
+            let player = self.next_by(self.dealer, offset);
+            let cards = self
+                .stock
+                .split_off(self.stock.len() - self.rules.hand_size);
+            self.hands[player.as_usize()] = cards;
+        }
+    }
+
+    /// Returns the player `offset` seats after `player`.
+    fn next_by(&self, player: PlayerId, offset: usize) -> PlayerId {
+        seat((player.as_usize() + offset) % self.seats)
+    }
+}
+
+impl CardGame for Scopa {
+    fn seats(&self) -> usize {
+        self.seats
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        Scopa::hand(self, player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        Scopa::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        &[]
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        self.current
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        Scopa::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        Scopa::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.history().last().map(|(_, effect)| *effect)
+    }
+
+    fn score(&self) -> Vec<u8> {
+        Scopa::score(self).to_vec()
+    }
+
+    fn status(&self) -> Status {
+        Scopa::status(self)
+    }
+
+    fn stock_len(&self) -> usize {
+        Scopa::stock_len(self)
+    }
+
+    fn table_cards(&self) -> Option<&[TressetteCard]> {
+        Some(self.table())
+    }
+
+    fn capture_options(&self, card: TressetteCard) -> Vec<Vec<TressetteCard>> {
+        Scopa::capture_options(self, card)
+    }
+
+    fn apply(&mut self, mv: &Move) -> Result<(), InvalidMove> {
+        let made = match mv {
+            Move::Play(card) => self.play_card(*card).is_ok(),
+            Move::Capture(card, capture) => self.play_capture(*card, capture).is_ok(),
+            _ => false,
+        };
+        made.then_some(()).ok_or_else(|| InvalidMove(mv.clone()))
+    }
+
+    fn scopas(&self) -> &[(PlayerId, TressetteCard)] {
+        Scopa::scopas(self)
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        Scopa::hand_summary(self)
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn dealer(&self) -> Option<PlayerId> {
+        Some(self.dealer)
+    }
+}
+
+/// The Seven of Coins.
+fn settebello() -> TressetteCard {
+    TressetteCard::new(ItalianRank::Seven, COINS)
+}
+
+/// Points each side makes with the cards it captured, for carte, denari,
+/// settebello and primiera. Ties give the point to nobody.
+pub fn hand_points(captured: &[Vec<TressetteCard>; 2]) -> Vec<(&'static str, [u8; 2])> {
+    let award = |values: [u32; 2]| match values[0].cmp(&values[1]) {
+        std::cmp::Ordering::Greater => [1, 0],
+        std::cmp::Ordering::Less => [0, 1],
+        std::cmp::Ordering::Equal => [0, 0],
+    };
+
+    vec![
+        (
+            "Cards",
+            award(captured.each_ref().map(|cards| cards.len() as u32)),
+        ),
+        (
+            "Coins",
+            award(
+                captured
+                    .each_ref()
+                    .map(|cards| cards.iter().filter(|c| c.suit() == COINS).count() as u32),
+            ),
+        ),
+        (
+            "Settebello",
+            award(
+                captured
+                    .each_ref()
+                    .map(|cards| cards.contains(&settebello()) as u32),
+            ),
+        ),
+        (
+            "Primiera",
+            award(captured.each_ref().map(|cards| primiera(cards))),
+        ),
+    ]
+}
+
+/// Points for the napola: the length of the run of Coins from the Ace up,
+/// when it reaches at least the Three.
+pub fn napola(cards: &[TressetteCard]) -> u8 {
+    let run = ItalianRank::iter()
+        .take_while(|rank| cards.contains(&TressetteCard::new(*rank, COINS)))
+        .count() as u8;
+    if run >= 3 { run } else { 0 }
+}
+
+/// Primiera of a set of cards: the sum of the best card of each suit, zero
+/// if a suit is missing.
+pub fn primiera(cards: &[TressetteCard]) -> u32 {
+    [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades]
+        .iter()
+        .map(|suit| {
+            cards
+                .iter()
+                .filter(|c| c.suit() == *suit)
+                .map(|c| primiera_value(*c))
+                .max()
+        })
+        .sum::<Option<u32>>()
+        .unwrap_or(0)
+}
+
+/// Value of a card towards the primiera.
+fn primiera_value(card: TressetteCard) -> u32 {
+    match card.rank() {
+        ItalianRank::Seven => 21,
+        ItalianRank::Six => 18,
+        ItalianRank::Ace => 16,
+        ItalianRank::Five => 15,
+        ItalianRank::Four => 14,
+        ItalianRank::Three => 13,
+        ItalianRank::Two => 12,
+        _ => 10,
+    }
+}
+
+/// Returns the player sitting at `index`.
+fn seat(index: usize) -> PlayerId {
+    PlayerId::try_from(index).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game of Scopa with `table` face up and the first player to move
+    /// holding `hand`, the other player holding a single card.
+    fn game_with(table: Vec<TressetteCard>, hand: Vec<TressetteCard>) -> Scopa {
+        let other = vec![TressetteCard::new(ItalianRank::King, Suit::Spades)];
+        Scopa::from_deal(vec![hand, other], table, Vec::new(), seat(0), 0)
+    }
+
+    #[test]
+    fn same_rank_capture_is_forced() {
+        let game = game_with(
+            vec![
+                TressetteCard::new(ItalianRank::Five, Suit::Clubs),
+                TressetteCard::new(ItalianRank::Two, COINS),
+                TressetteCard::new(ItalianRank::Three, Suit::Spades),
+            ],
+            vec![TressetteCard::new(ItalianRank::Five, Suit::Hearts)],
+        );
+        assert_eq!(
+            game.capture_options(TressetteCard::new(ItalianRank::Five, Suit::Hearts)),
+            vec![vec![TressetteCard::new(ItalianRank::Five, Suit::Clubs)]]
+        );
+    }
+
+    #[test]
+    fn sum_captures_when_no_same_rank() {
+        let mut game = game_with(
+            vec![
+                TressetteCard::new(ItalianRank::Ace, Suit::Clubs),
+                TressetteCard::new(ItalianRank::Four, COINS),
+                TressetteCard::new(ItalianRank::Two, Suit::Spades),
+                TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+            ],
+            vec![TressetteCard::new(ItalianRank::Five, Suit::Hearts)],
+        );
+        let options = game.capture_options(TressetteCard::new(ItalianRank::Five, Suit::Hearts));
+        assert_eq!(
+            options,
+            vec![
+                vec![
+                    TressetteCard::new(ItalianRank::Ace, Suit::Clubs),
+                    TressetteCard::new(ItalianRank::Four, COINS)
+                ],
+                vec![
+                    TressetteCard::new(ItalianRank::Two, Suit::Spades),
+                    TressetteCard::new(ItalianRank::Three, Suit::Hearts)
+                ],
+            ]
+        );
+        // Anything else is refused.
+        assert!(
+            game.play_capture(
+                TressetteCard::new(ItalianRank::Five, Suit::Hearts),
+                &[
+                    TressetteCard::new(ItalianRank::Ace, Suit::Clubs),
+                    TressetteCard::new(ItalianRank::Three, Suit::Hearts)
+                ],
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn clearing_the_table_is_a_scopa() {
+        let mut game = game_with(
+            vec![
+                TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+                TressetteCard::new(ItalianRank::Four, Suit::Clubs),
+            ],
+            vec![
+                TressetteCard::new(ItalianRank::Seven, Suit::Spades),
+                TressetteCard::new(ItalianRank::King, Suit::Hearts),
+            ],
+        );
+        let effect = game.play_card(TressetteCard::new(ItalianRank::Seven, Suit::Spades));
+        assert_eq!(effect, Ok(MoveEffect::TrickCompleted { winner: seat(0) }));
+        assert_eq!(
+            game.scopas(),
+            [(
+                seat(0),
+                TressetteCard::new(ItalianRank::Seven, Suit::Spades)
+            )]
+        );
+    }
+
+    #[test]
+    fn last_card_of_the_hand_is_no_scopa() {
+        let mut game = Scopa::from_deal(
+            vec![
+                vec![TressetteCard::new(ItalianRank::Seven, Suit::Spades)],
+                Vec::new(),
+            ],
+            vec![
+                TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+                TressetteCard::new(ItalianRank::Four, Suit::Clubs),
+            ],
+            Vec::new(),
+            seat(0),
+            0,
+        );
+        let effect = game.play_card(TressetteCard::new(ItalianRank::Seven, Suit::Spades));
+        assert_eq!(
+            effect,
+            Ok(MoveEffect::HandComplete {
+                trick_winner: seat(0)
+            })
+        );
+        // Only the point for the most cards.
+        assert_eq!(game.score(), [1, 0]);
+    }
+
+    #[test]
+    fn primiera_goes_to_the_best_cards_of_each_suit() {
+        let sevens: Vec<TressetteCard> = [Suit::Hearts, COINS, Suit::Clubs, Suit::Spades]
+            .map(|suit| TressetteCard::new(ItalianRank::Seven, suit))
+            .to_vec();
+        let sixes: Vec<TressetteCard> = [Suit::Hearts, COINS, Suit::Clubs, Suit::Spades]
+            .map(|suit| TressetteCard::new(ItalianRank::Six, suit))
+            .to_vec();
+        assert_eq!(primiera(&sevens), 84);
+        assert_eq!(primiera(&sixes), 72);
+        // A missing suit leaves no primiera at all.
+        assert_eq!(primiera(&sevens[..3]), 0);
+
+        let primiera_line = |captured: [Vec<TressetteCard>; 2]| {
+            hand_points(&captured)
+                .into_iter()
+                .find(|(label, _)| *label == "Primiera")
+                .unwrap()
+                .1
+        };
+        assert_eq!(primiera_line([sevens.clone(), sixes.clone()]), [1, 0]);
+        assert_eq!(primiera_line([sixes.clone(), sevens]), [0, 1]);
+        // A tie gives the point to nobody.
+        assert_eq!(primiera_line([sixes.clone(), sixes]), [0, 0]);
+    }
+
+    #[test]
+    fn settebello_is_worth_a_point() {
+        let with_settebello = vec![
+            settebello(),
+            TressetteCard::new(ItalianRank::Four, Suit::Clubs),
+        ];
+        let without = vec![
+            TressetteCard::new(ItalianRank::Six, COINS),
+            TressetteCard::new(ItalianRank::Five, Suit::Clubs),
+        ];
+        let points = hand_points(&[with_settebello, without]);
+        assert!(points.contains(&("Settebello", [1, 0])));
+        // Nothing else tells the two sides apart.
+        assert_eq!(
+            points
+                .iter()
+                .map(|(_, points)| points[0] + points[1])
+                .sum::<u8>(),
+            1
+        );
+    }
+
+    #[test]
+    fn napola_counts_the_run_of_coins_from_the_ace() {
+        let run = |ranks: &[ItalianRank]| -> Vec<TressetteCard> {
+            ranks
+                .iter()
+                .map(|rank| TressetteCard::new(*rank, COINS))
+                .collect()
+        };
+        assert_eq!(napola(&run(&[ItalianRank::Ace, ItalianRank::Two])), 0);
+        assert_eq!(
+            napola(&run(&[
+                ItalianRank::Ace,
+                ItalianRank::Two,
+                ItalianRank::Three
+            ])),
+            3
+        );
+        assert_eq!(
+            napola(&run(&[
+                ItalianRank::Ace,
+                ItalianRank::Two,
+                ItalianRank::Three,
+                ItalianRank::Four,
+                ItalianRank::Five,
+                ItalianRank::Seven,
+            ])),
+            5
+        );
+        assert_eq!(
+            napola(&run(&[
+                ItalianRank::Two,
+                ItalianRank::Three,
+                ItalianRank::Four
+            ])),
+            0
+        );
+    }
+}
@@ -0,0 +1,892 @@
+//! Three-player Tressette ("Terziglio", also known as "Calabresella").
+//!
+//! Each player gets 12 cards and the last 4 form the monte. Starting from the
+//! player after the dealer, everyone either passes or calls ("chiamo"); the
+//! first to call plays alone against the other two. The caller may ask for a
+//! Three they don't hold, giving back a card of their choice to whoever had
+//! it, then takes the monte and discards four cards, which count towards
+//! their tricks. If everyone passes the cards are dealt again.
+//!
+//! A hand is worth 11 points. If the caller takes at least 6 they score what
+//! they took, otherwise each defender scores what the pair took together.
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use shuftlib::{
+    core::{Suit, italian::ItalianRank},
+    tressette::{Error, SCORE_TO_WIN, Status, TressetteCard},
+    trick_taking::PlayerId,
+};
+use strum::IntoEnumIterator;
+
+use super::{
+    CardGame, MoveEffect, PendingPhase, SummaryLine, card_lines,
+    record::{InvalidMove, Move},
+    shuffled_deck, thirds, took_every_trick,
+};
+
+/// Number of seats at the table.
+pub const SEATS: usize = 3;
+
+/// Number of cards dealt to each player at the start of a hand.
+const HAND_SIZE: usize = 12;
+
+/// Number of cards left face down in the monte.
+pub const MONTE_SIZE: usize = 4;
+
+/// Points the caller needs to win the hand.
+const POINTS_TO_MAKE: u8 = 6;
+
+/// Hand value, in thirds, from which bots call.
+const BOT_CALL_THRESHOLD: u8 = 14;
+
+/// What the table is waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Players are calling or passing in turn.
+    Auction,
+    /// The caller is choosing which Three to ask for.
+    Calling,
+    /// The caller gives a card back to whoever handed over the called Three.
+    GiveBack { to: PlayerId },
+    /// The caller has taken the monte and has to discard.
+    Discard,
+    /// Tricks are being played.
+    Play,
+}
+
+/// A bid during the auction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bid {
+    /// "Passo".
+    Pass,
+    /// "Chiamo": play alone against the other two.
+    Call,
+}
+
+/// The effect of a bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BidEffect {
+    /// The turn moved to the next bidder.
+    Passed,
+    /// The bidder became the caller.
+    Called,
+    /// Everybody passed and a new hand was dealt.
+    Redealt,
+}
+
+/// An error that can occur before tricks are played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuctionError {
+    /// The action doesn't belong to the current phase.
+    WrongPhase(Phase),
+    /// The card can't be called.
+    NotCallable(TressetteCard),
+    /// The caller doesn't hold the card.
+    CardNotInHand(TressetteCard),
+    /// The caller must discard exactly `expected` distinct cards.
+    WrongDiscard { expected: usize },
+}
+
+#[derive(Debug)]
+pub struct Terziglio {
+    rng: StdRng,
+    hands: [Vec<TressetteCard>; SEATS],
+    monte: Vec<TressetteCard>,
+    discarded: Vec<TressetteCard>,
+    phase: Phase,
+    dealer: PlayerId,
+    bidder: PlayerId,
+    passes: usize,
+    caller: Option<PlayerId>,
+    trick: [Option<TressetteCard>; SEATS],
+    leader: PlayerId,
+    captured: [Vec<TressetteCard>; SEATS],
+    score: [u8; SEATS],
+    history: Vec<(TressetteCard, MoveEffect)>,
+    /// Points each player made in the last hand completed.
+    hand_points: [u8; SEATS],
+    /// Player that took every trick of the last hand completed, if any.
+    cappotto: Option<usize>,
+    /// What each player made in the last hand completed.
+    summary: Vec<SummaryLine>,
+}
+
+impl Terziglio {
+    /// Creates a new game with the first hand dealt and the auction open.
+    /// Games created from the same `seed` deal the same cards.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dealer = seat(rng.random_range(0..SEATS));
+        let mut game = Self::seated(rng, dealer);
+        game.deal();
+        game
+    }
+
+    /// Creates a new game with the first hand dealt as in `hands` and
+    /// `monte`, `leader` opening the auction. The hands after it are
+    /// shuffled from `seed`.
+    pub fn from_deal(
+        hands: [Vec<TressetteCard>; SEATS],
+        monte: Vec<TressetteCard>,
+        leader: PlayerId,
+        seed: u64,
+    ) -> Self {
+        let dealer = seat(leader.as_usize() + SEATS - 1);
+        Self {
+            hands,
+            monte,
+            ..Self::seated(StdRng::seed_from_u64(seed), dealer)
+        }
+    }
+
+    /// A game with no card dealt yet and `dealer` to deal.
+    fn seated(rng: StdRng, dealer: PlayerId) -> Self {
+        Self {
+            rng,
+            hands: Default::default(),
+            monte: Vec::with_capacity(MONTE_SIZE),
+            discarded: Vec::with_capacity(MONTE_SIZE),
+            phase: Phase::Auction,
+            dealer,
+            bidder: next(dealer),
+            passes: 0,
+            caller: None,
+            trick: [None; SEATS],
+            leader: next(dealer),
+            captured: Default::default(),
+            score: [0; SEATS],
+            history: Vec::new(),
+            hand_points: [0; SEATS],
+            cappotto: None,
+            summary: Vec::new(),
+        }
+    }
+
+    /// Returns what the table is waiting for.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Returns the player playing alone this hand, once somebody called.
+    pub fn caller(&self) -> Option<PlayerId> {
+        self.caller
+    }
+
+    /// Returns the player who has to act: the bidder during the auction, the
+    /// caller while exchanging cards and the next to play afterwards.
+    pub fn current_player(&self) -> PlayerId {
+        match (self.phase, self.caller) {
+            (Phase::Auction, _) => self.bidder,
+            (Phase::Play, _) | (_, None) => {
+                let played = self.trick.iter().flatten().count();
+                seat(self.leader.as_usize() + played)
+            }
+            (_, Some(caller)) => caller,
+        }
+    }
+
+    /// Returns the score of each player.
+    pub fn score(&self) -> [u8; SEATS] {
+        self.score
+    }
+
+    /// Returns the current status of the game.
+    pub fn status(&self) -> Status {
+        match leader_of(&self.score) {
+            Some(winner) if self.score[winner] >= SCORE_TO_WIN => Status::Finished {
+                winner: winner as u8,
+            },
+            _ => Status::Ongoing,
+        }
+    }
+
+    /// Returns the cards in the given player's hand.
+    pub fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        &self.hands[player.as_usize()]
+    }
+
+    /// Returns the cards still in the monte.
+    pub fn monte(&self) -> &[TressetteCard] {
+        &self.monte
+    }
+
+    /// Returns the cards played in the ongoing trick, indexed by player.
+    pub fn current_trick(&self) -> &[Option<TressetteCard>; SEATS] {
+        &self.trick
+    }
+
+    /// Returns the player who led the current trick.
+    pub fn trick_leader(&self) -> PlayerId {
+        self.leader
+    }
+
+    /// Returns the card history.
+    pub fn history(&self) -> &[(TressetteCard, MoveEffect)] {
+        &self.history
+    }
+
+    /// Passes or calls for the current bidder.
+    pub fn bid(&mut self, bid: Bid) -> Result<BidEffect, AuctionError> {
+        if self.phase != Phase::Auction {
+            return Err(AuctionError::WrongPhase(self.phase));
+        }
+
+        match bid {
+            Bid::Pass => {
+                self.passes += 1;
+                if self.passes == SEATS {
+                    self.dealer = next(self.dealer);
+                    self.deal();
+                    Ok(BidEffect::Redealt)
+                } else {
+                    self.bidder = next(self.bidder);
+                    Ok(BidEffect::Passed)
+                }
+            }
+            Bid::Call => {
+                self.caller = Some(self.bidder);
+                if self.callable_threes().is_empty() {
+                    self.take_monte();
+                } else {
+                    self.phase = Phase::Calling;
+                }
+                Ok(BidEffect::Called)
+            }
+        }
+    }
+
+    /// Returns the Threes the caller can ask for, that is the ones they don't
+    /// hold.
+    pub fn callable_threes(&self) -> Vec<TressetteCard> {
+        let Some(caller) = self.caller else {
+            return vec![];
+        };
+        Suit::iter()
+            .map(|suit| TressetteCard::new(ItalianRank::Three, suit))
+            .filter(|three| !self.hands[caller.as_usize()].contains(three))
+            .collect()
+    }
+
+    /// Asks for `three`. Whoever holds it hands it over to the caller and is
+    /// returned; if it's in the monte nobody does and the caller takes the
+    /// monte straight away.
+    pub fn call_three(&mut self, three: TressetteCard) -> Result<Option<PlayerId>, AuctionError> {
+        let Some(caller) = self.caller.filter(|_| self.phase == Phase::Calling) else {
+            return Err(AuctionError::WrongPhase(self.phase));
+        };
+        if !self.callable_threes().contains(&three) {
+            return Err(AuctionError::NotCallable(three));
+        }
+
+        let holder = (0..SEATS)
+            .map(seat)
+            .find(|player| self.hands[player.as_usize()].contains(&three));
+        match holder {
+            Some(holder) => {
+                self.hands[holder.as_usize()].retain(|&c| c != three);
+                self.hands[caller.as_usize()].push(three);
+                self.phase = Phase::GiveBack { to: holder };
+            }
+            None => self.take_monte(),
+        }
+        Ok(holder)
+    }
+
+    /// Gives `card` from the caller's hand to the player who handed over the
+    /// called Three, then lets the caller take the monte.
+    pub fn give_back(&mut self, card: TressetteCard) -> Result<(), AuctionError> {
+        let (Some(caller), Phase::GiveBack { to }) = (self.caller, self.phase) else {
+            return Err(AuctionError::WrongPhase(self.phase));
+        };
+        if !self.hands[caller.as_usize()].contains(&card) {
+            return Err(AuctionError::CardNotInHand(card));
+        }
+
+        self.hands[caller.as_usize()].retain(|&c| c != card);
+        self.hands[to.as_usize()].push(card);
+        self.take_monte();
+        Ok(())
+    }
+
+    /// Discards `cards` from the caller's hand, which opens the play.
+    pub fn discard(&mut self, cards: &[TressetteCard]) -> Result<(), AuctionError> {
+        let Some(caller) = self.caller.filter(|_| self.phase == Phase::Discard) else {
+            return Err(AuctionError::WrongPhase(self.phase));
+        };
+        let distinct = cards
+            .iter()
+            .enumerate()
+            .all(|(i, card)| !cards[..i].contains(card));
+        if cards.len() != MONTE_SIZE || !distinct {
+            return Err(AuctionError::WrongDiscard {
+                expected: MONTE_SIZE,
+            });
+        }
+        if let Some(card) = cards
+            .iter()
+            .find(|card| !self.hands[caller.as_usize()].contains(card))
+        {
+            return Err(AuctionError::CardNotInHand(*card));
+        }
+
+        self.hands[caller.as_usize()].retain(|card| !cards.contains(card));
+        self.discarded = cards.to_vec();
+        self.phase = Phase::Play;
+        Ok(())
+    }
+
+    /// Returns all legal cards for the current player, none before the play
+    /// starts.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if self.phase != Phase::Play || matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+
+        let hand = &self.hands[self.current_player().as_usize()];
+        match self.trick[self.leader.as_usize()] {
+            Some(led) if hand.iter().any(|c| c.suit() == led.suit()) => hand
+                .iter()
+                .filter(|c| c.suit() == led.suit())
+                .copied()
+                .collect(),
+            _ => hand.clone(),
+        }
+    }
+
+    /// Plays a card for the current player.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+        if self.phase != Phase::Play {
+            return Err(Error::InternalError(format!(
+                "Cannot play a card during {:?}",
+                self.phase
+            )));
+        }
+
+        let player = self.current_player();
+        if !self.legal_cards().contains(&card) {
+            if !self.hand(player).contains(&card) {
+                return Err(Error::CardNotInHand { card, player });
+            }
+            let required_suit = self.trick[self.leader.as_usize()]
+                .map(|c| c.suit())
+                .ok_or(Error::InternalError("No leading suit".to_string()))?;
+            return Err(Error::MustFollowSuit {
+                suit: card.suit(),
+                required_suit,
+            });
+        }
+
+        self.hands[player.as_usize()].retain(|&c| c != card);
+        self.trick[player.as_usize()] = Some(card);
+
+        let effect = if self.trick.iter().all(Option::is_some) {
+            self.complete_trick()
+        } else {
+            MoveEffect::CardPlayed
+        };
+        self.history.push((card, effect));
+
+        Ok(effect)
+    }
+
+    /// Bid a bot would make with the current bidder's hand.
+    pub fn bot_bid(&self) -> Bid {
+        let value: u8 = self.hands[self.bidder.as_usize()]
+            .iter()
+            .map(|c| thirds(*c))
+            .sum();
+        if value >= BOT_CALL_THRESHOLD {
+            Bid::Call
+        } else {
+            Bid::Pass
+        }
+    }
+
+    /// Three a bot would ask for: the one of its longest suit.
+    pub fn bot_call(&self) -> Option<TressetteCard> {
+        let hand = &self.hands[self.current_player().as_usize()];
+        self.callable_threes()
+            .into_iter()
+            .max_by_key(|three| hand.iter().filter(|c| c.suit() == three.suit()).count())
+    }
+
+    /// Cards a bot would get rid of, `count` of them: the lowest ones.
+    pub fn bot_discard(&self, count: usize) -> Vec<TressetteCard> {
+        let mut hand = self.hands[self.current_player().as_usize()].clone();
+        hand.sort_by_key(|c| (thirds(*c), *c));
+        hand.truncate(count);
+        hand
+    }
+
+    fn complete_trick(&mut self) -> MoveEffect {
+        // The highest card of the led suit takes the trick.
+        let led = self.trick[self.leader.as_usize()].map(|c| c.suit());
+        let winner = self
+            .trick
+            .iter()
+            .enumerate()
+            .filter_map(|(player, card)| {
+                card.filter(|c| Some(c.suit()) == led).map(|c| (player, c))
+            })
+            .max_by_key(|(_, card)| *card)
+            .map_or(self.leader, |(player, _)| seat(player));
+
+        self.captured[winner.as_usize()].extend(self.trick.iter().flatten());
+        self.trick = [None; SEATS];
+        self.leader = winner;
+
+        if self.hands.iter().all(Vec::is_empty) {
+            self.complete_hand(winner)
+        } else {
+            MoveEffect::TrickCompleted { winner }
+        }
+    }
+
+    fn complete_hand(&mut self, last_trick_winner: PlayerId) -> MoveEffect {
+        let caller = self.caller.unwrap_or(self.leader);
+        let mut caller_thirds: u8 = self.discarded.iter().map(|c| thirds(*c)).sum();
+        let mut defenders_thirds = 0;
+        for (player, cards) in self.captured.iter().enumerate() {
+            let value: u8 = cards.iter().map(|c| thirds(*c)).sum();
+            if player == caller.as_usize() {
+                caller_thirds += value;
+            } else {
+                defenders_thirds += value;
+            }
+        }
+
+        let mut caller_points = caller_thirds / 3;
+        let mut defenders_points = defenders_thirds / 3;
+        // The last trick is worth an extra point.
+        if last_trick_winner == caller {
+            caller_points += 1;
+        } else {
+            defenders_points += 1;
+        }
+
+        let mut hand = [0; SEATS];
+        if caller_points >= POINTS_TO_MAKE {
+            hand[caller.as_usize()] = caller_points;
+        } else {
+            for player in (0..SEATS).filter(|p| *p != caller.as_usize()) {
+                hand[player] = defenders_points;
+            }
+        }
+        for (score, points) in self.score.iter_mut().zip(hand) {
+            *score += points;
+        }
+
+        self.hand_points = hand;
+        self.cappotto = took_every_trick(&self.captured);
+
+        let mut discarded = [0; SEATS];
+        discarded[caller.as_usize()] = self.discarded.iter().map(|c| thirds(*c)).sum();
+        let mut last_trick = [0; SEATS];
+        last_trick[last_trick_winner.as_usize()] = 1;
+        self.summary = card_lines(&self.captured);
+        self.summary.extend([
+            SummaryLine::new("Thirds from the discards", &discarded),
+            SummaryLine::new("Last trick", &last_trick),
+            SummaryLine::new("Hand", &hand),
+            SummaryLine::new("Total", &self.score),
+        ]);
+
+        if matches!(self.status(), Status::Finished { .. }) {
+            MoveEffect::GameOver {
+                trick_winner: last_trick_winner,
+            }
+        } else {
+            self.dealer = next(self.dealer);
+            self.deal();
+            MoveEffect::HandComplete {
+                trick_winner: last_trick_winner,
+            }
+        }
+    }
+
+    /// Moves the monte into the caller's hand.
+    fn take_monte(&mut self) {
+        if let Some(caller) = self.caller {
+            self.hands[caller.as_usize()].append(&mut self.monte);
+        }
+        self.phase = Phase::Discard;
+    }
+
+    /// Shuffles a full deck, deals every hand and leaves the monte aside, then
+    /// opens the auction.
+    fn deal(&mut self) {
+        let mut deck = shuffled_deck(&mut self.rng);
+
+        self.hands = Default::default();
+        self.captured = Default::default();
+        self.discarded.clear();
+        let mut to_deal_to = next(self.dealer);
+        while deck.len() > MONTE_SIZE {
+            if let Some(cards) = deck.draw_n(MONTE_SIZE) {
+                self.hands[to_deal_to.as_usize()].extend(cards);
+            }
+            to_deal_to = next(to_deal_to);
+        }
+        self.monte = deck.into_iter().collect();
+        debug_assert!(self.hands.iter().all(|h| h.len() == HAND_SIZE));
+
+        self.phase = Phase::Auction;
+        self.bidder = next(self.dealer);
+        self.passes = 0;
+        self.caller = None;
+        self.leader = next(self.dealer);
+        self.trick = [None; SEATS];
+    }
+}
+
+impl CardGame for Terziglio {
+    fn seats(&self) -> usize {
+        SEATS
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        Terziglio::hand(self, player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        Terziglio::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        Terziglio::current_trick(self)
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        Terziglio::trick_leader(self)
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        Terziglio::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        Terziglio::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.history().last().map(|(_, effect)| *effect)
+    }
+
+    fn score(&self) -> Vec<u8> {
+        Terziglio::score(self).to_vec()
+    }
+
+    fn status(&self) -> Status {
+        Terziglio::status(self)
+    }
+
+    fn stock_len(&self) -> usize {
+        self.monte().len()
+    }
+
+    fn pending_phase(&self) -> Option<PendingPhase> {
+        match self.phase {
+            Phase::Auction => Some(PendingPhase::Bid),
+            Phase::Calling => Some(PendingPhase::CallThree),
+            Phase::GiveBack { to } => Some(PendingPhase::GiveBack { to }),
+            Phase::Discard => Some(PendingPhase::Discard { count: MONTE_SIZE }),
+            Phase::Play => None,
+        }
+    }
+
+    fn pending_moves(&self) -> Vec<Move> {
+        match self.phase {
+            Phase::Auction => vec![Move::Bid(Bid::Call), Move::Bid(Bid::Pass)],
+            Phase::Calling => self
+                .callable_threes()
+                .into_iter()
+                .map(Move::CallThree)
+                .collect(),
+            Phase::GiveBack { .. } => self.hands[self.current_player().as_usize()]
+                .iter()
+                .map(|card| Move::GiveBack(*card))
+                .collect(),
+            Phase::Discard | Phase::Play => Vec::new(),
+        }
+    }
+
+    fn bot_move(&self) -> Option<Move> {
+        match self.phase {
+            Phase::Auction => Some(Move::Bid(self.bot_bid())),
+            Phase::Calling => self.bot_call().map(Move::CallThree),
+            Phase::GiveBack { .. } => self.bot_discard(1).first().copied().map(Move::GiveBack),
+            Phase::Discard => Some(Move::Discard(self.bot_discard(MONTE_SIZE))),
+            Phase::Play => None,
+        }
+    }
+
+    fn apply(&mut self, mv: &Move) -> Result<(), InvalidMove> {
+        let made = match mv {
+            Move::Play(card) => self.play_card(*card).is_ok(),
+            Move::Bid(bid) => self.bid(*bid).is_ok(),
+            Move::CallThree(three) => self.call_three(*three).is_ok(),
+            Move::GiveBack(card) => self.give_back(*card).is_ok(),
+            Move::Discard(cards) => self.discard(cards).is_ok(),
+            _ => false,
+        };
+        made.then_some(()).ok_or_else(|| InvalidMove(mv.clone()))
+    }
+
+    fn shows_drawn_cards(&self) -> bool {
+        true
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn cappotto(&self) -> Option<usize> {
+        self.cappotto
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+
+    fn dealer(&self) -> Option<PlayerId> {
+        Some(self.dealer)
+    }
+}
+
+impl Default for Terziglio {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+/// Returns the seat after `player`.
+fn next(player: PlayerId) -> PlayerId {
+    seat(player.as_usize() + 1)
+}
+
+/// Returns the seat with index `index`, wrapping around the table.
+fn seat(index: usize) -> PlayerId {
+    PlayerId::try_from(index % SEATS).unwrap()
+}
+
+/// Returns the index of the only player with the highest score, if any.
+fn leader_of(score: &[u8]) -> Option<usize> {
+    let best = *score.iter().max()?;
+    let mut leaders = score.iter().enumerate().filter(|(_, s)| **s == best);
+    let (leader, _) = leaders.next()?;
+    leaders.next().is_none().then_some(leader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game with the auction opened by player 0 and `hands` dealt, the
+    /// monte holding `monte`.
+    fn game_with(hands: [Vec<TressetteCard>; SEATS], monte: Vec<TressetteCard>) -> Terziglio {
+        Terziglio::from_deal(hands, monte, seat(0), 0)
+    }
+
+    /// A game where player 0 called, asked for `three` and gave it straight
+    /// back, then discarded the whole `monte`.
+    fn playing_game(
+        hands: [Vec<TressetteCard>; SEATS],
+        monte: Vec<TressetteCard>,
+        three: TressetteCard,
+    ) -> Terziglio {
+        let mut game = game_with(hands, monte.clone());
+        assert_eq!(game.bid(Bid::Call), Ok(BidEffect::Called));
+        game.call_three(three).unwrap();
+        game.give_back(three).unwrap();
+        game.discard(&monte).unwrap();
+        game
+    }
+
+    /// A game where player 0 called and is now choosing the Three to ask
+    /// for, holding only the Three of Hearts.
+    fn calling_game() -> Terziglio {
+        let mut game = game_with(
+            [
+                vec![
+                    TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Four, Suit::Clubs),
+                ],
+                vec![TressetteCard::new(ItalianRank::Three, Suit::Clubs)],
+                vec![TressetteCard::new(ItalianRank::Three, Suit::Spades)],
+            ],
+            vec![
+                TressetteCard::new(ItalianRank::Three, Suit::Diamonds),
+                TressetteCard::new(ItalianRank::Ace, Suit::Hearts),
+                TressetteCard::new(ItalianRank::Ace, Suit::Clubs),
+                TressetteCard::new(ItalianRank::Ace, Suit::Spades),
+            ],
+        );
+        assert_eq!(game.bid(Bid::Call), Ok(BidEffect::Called));
+        game
+    }
+
+    #[test]
+    fn first_to_call_plays_alone() {
+        let mut game = game_with(Default::default(), Vec::new());
+        assert_eq!(game.current_player(), seat(0));
+        assert_eq!(game.bid(Bid::Pass), Ok(BidEffect::Passed));
+        assert_eq!(game.current_player(), seat(1));
+        assert_eq!(game.bid(Bid::Call), Ok(BidEffect::Called));
+        assert_eq!(game.caller(), Some(seat(1)));
+        // Holding no Three, the caller may ask for any of them.
+        assert_eq!(game.phase(), Phase::Calling);
+        assert_eq!(game.callable_threes().len(), 4);
+        assert_eq!(
+            game.bid(Bid::Pass),
+            Err(AuctionError::WrongPhase(Phase::Calling))
+        );
+    }
+
+    #[test]
+    fn everybody_passing_deals_again() {
+        let mut game = Terziglio::new(0);
+        let dealer = game.dealer;
+        assert_eq!(game.bid(Bid::Pass), Ok(BidEffect::Passed));
+        assert_eq!(game.bid(Bid::Pass), Ok(BidEffect::Passed));
+        assert_eq!(game.bid(Bid::Pass), Ok(BidEffect::Redealt));
+        assert_eq!(game.dealer, next(dealer));
+        assert_eq!(game.phase(), Phase::Auction);
+        assert_eq!(game.current_player(), next(next(dealer)));
+        assert!(game.hands.iter().all(|hand| hand.len() == HAND_SIZE));
+        assert_eq!(game.monte().len(), MONTE_SIZE);
+    }
+
+    #[test]
+    fn called_three_is_handed_over_and_a_card_given_back() {
+        let mut game = calling_game();
+        let three = TressetteCard::new(ItalianRank::Three, Suit::Clubs);
+        // The caller can't ask for the Three they hold.
+        assert_eq!(
+            game.call_three(TressetteCard::new(ItalianRank::Three, Suit::Hearts)),
+            Err(AuctionError::NotCallable(TressetteCard::new(
+                ItalianRank::Three,
+                Suit::Hearts
+            )))
+        );
+        assert_eq!(game.call_three(three), Ok(Some(seat(1))));
+        assert!(game.hand(seat(0)).contains(&three));
+        assert!(game.hand(seat(1)).is_empty());
+        assert_eq!(game.phase(), Phase::GiveBack { to: seat(1) });
+
+        let four = TressetteCard::new(ItalianRank::Four, Suit::Clubs);
+        assert_eq!(
+            game.give_back(TressetteCard::new(ItalianRank::Ace, Suit::Hearts)),
+            Err(AuctionError::CardNotInHand(TressetteCard::new(
+                ItalianRank::Ace,
+                Suit::Hearts
+            )))
+        );
+        assert_eq!(game.give_back(four), Ok(()));
+        assert_eq!(game.hand(seat(1)), [four]);
+        // Then the caller takes the monte.
+        assert_eq!(game.phase(), Phase::Discard);
+        assert!(game.monte().is_empty());
+        assert_eq!(game.hand(seat(0)).len(), 2 + MONTE_SIZE);
+    }
+
+    #[test]
+    fn three_in_the_monte_is_taken_with_it() {
+        let mut game = calling_game();
+        assert_eq!(
+            game.call_three(TressetteCard::new(ItalianRank::Three, Suit::Diamonds)),
+            Ok(None)
+        );
+        assert_eq!(game.phase(), Phase::Discard);
+        assert!(
+            game.hand(seat(0))
+                .contains(&TressetteCard::new(ItalianRank::Three, Suit::Diamonds))
+        );
+    }
+
+    #[test]
+    fn caller_discards_four_cards_from_their_hand() {
+        let mut game = calling_game();
+        game.call_three(TressetteCard::new(ItalianRank::Three, Suit::Diamonds))
+            .unwrap();
+        let hand = game.hand(seat(0)).to_vec();
+
+        assert_eq!(
+            game.discard(&hand[..3]),
+            Err(AuctionError::WrongDiscard {
+                expected: MONTE_SIZE
+            })
+        );
+        assert_eq!(
+            game.discard(&[hand[0], hand[0], hand[1], hand[2]]),
+            Err(AuctionError::WrongDiscard {
+                expected: MONTE_SIZE
+            })
+        );
+        let not_held = TressetteCard::new(ItalianRank::Three, Suit::Spades);
+        assert_eq!(
+            game.discard(&[hand[0], hand[1], hand[2], not_held]),
+            Err(AuctionError::CardNotInHand(not_held))
+        );
+
+        assert_eq!(game.discard(&hand[..4]), Ok(()));
+        assert_eq!(game.phase(), Phase::Play);
+        assert_eq!(game.hand(seat(0)), &hand[4..]);
+    }
+
+    #[test]
+    fn discarded_cards_count_for_the_caller() {
+        let three = TressetteCard::new(ItalianRank::Three, Suit::Diamonds);
+        let mut game = playing_game(
+            [
+                vec![TressetteCard::new(ItalianRank::Three, Suit::Hearts)],
+                vec![TressetteCard::new(ItalianRank::Two, Suit::Hearts)],
+                vec![three],
+            ],
+            [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades]
+                .map(|suit| TressetteCard::new(ItalianRank::Ace, suit))
+                .to_vec(),
+            three,
+        );
+
+        game.play_card(TressetteCard::new(ItalianRank::Three, Suit::Hearts))
+            .unwrap();
+        game.play_card(TressetteCard::new(ItalianRank::Two, Suit::Hearts))
+            .unwrap();
+        assert_eq!(
+            game.play_card(three),
+            Ok(MoveEffect::HandComplete {
+                trick_winner: seat(0)
+            })
+        );
+        // Four aces discarded, a point from the trick and one for the last
+        // trick make the six the caller needs.
+        assert_eq!(game.score(), [6, 0, 0]);
+    }
+
+    #[test]
+    fn defenders_score_when_the_caller_falls_short() {
+        let mut game = playing_game(
+            [
+                vec![TressetteCard::new(ItalianRank::Four, Suit::Hearts)],
+                vec![TressetteCard::new(ItalianRank::Three, Suit::Hearts)],
+                vec![TressetteCard::new(ItalianRank::Ace, Suit::Hearts)],
+            ],
+            [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades]
+                .map(|suit| TressetteCard::new(ItalianRank::Five, suit))
+                .to_vec(),
+            TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+        );
+
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Hearts))
+            .unwrap();
+        game.play_card(TressetteCard::new(ItalianRank::Three, Suit::Hearts))
+            .unwrap();
+        game.play_card(TressetteCard::new(ItalianRank::Ace, Suit::Hearts))
+            .unwrap();
+        // The ace, the three and the last trick, counted for both defenders.
+        assert_eq!(game.score(), [0, 2, 2]);
+    }
+}
@@ -0,0 +1,513 @@
+//! Two-player Tressette ("Tressette a due").
+//!
+//! Each player gets 10 cards and the remaining 20 form the stock. After every
+//! trick the winner draws first and the loser second, both cards being shown
+//! to the table. Once the stock is empty the last 10 tricks are played out.
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+use shuftlib::{
+    core::deck::Deck,
+    tressette::{Error, Status, TressetteCard, TressetteRules},
+    trick_taking::{PlayerId, TrickTakingGame},
+};
+
+use super::{
+    CardGame, MoveEffect, SummaryLine, card_lines, shuffled_deck, thirds, took_every_trick,
+};
+
+/// Number of seats at the table.
+pub const SEATS: usize = 2;
+
+/// Number of cards dealt to each player at the start of a hand.
+const HAND_SIZE: usize = 10;
+
+#[derive(Debug)]
+pub struct TressetteDue {
+    rng: StdRng,
+    stock: Deck<TressetteCard>,
+    hands: [Vec<TressetteCard>; SEATS],
+    trick: [Option<TressetteCard>; SEATS],
+    captured: [Vec<TressetteCard>; SEATS],
+    leader: PlayerId,
+    dealer: PlayerId,
+    score: (u8, u8),
+    history: Vec<(TressetteCard, MoveEffect)>,
+    /// Points each player made in the last hand completed.
+    hand_points: [u8; SEATS],
+    /// Player that took every trick of the last hand completed, if any.
+    cappotto: Option<usize>,
+    /// What each player made in the last hand completed.
+    summary: Vec<SummaryLine>,
+}
+
+impl TressetteDue {
+    /// Creates a new game with the first hand already dealt. Games created
+    /// from the same `seed` deal the same cards.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dealer = if rng.random_bool(0.5) {
+            PlayerId::PLAYER_0
+        } else {
+            PlayerId::PLAYER_1
+        };
+        let mut game = Self::seated(rng, dealer);
+        game.deal();
+        game
+    }
+
+    /// Creates a new game with the first hand dealt as in `hands`, `stock`
+    /// left to draw from its end and `leader` playing first. The hands after
+    /// it are shuffled from `seed`.
+    pub fn from_deal(
+        hands: [Vec<TressetteCard>; SEATS],
+        stock: Vec<TressetteCard>,
+        leader: PlayerId,
+        seed: u64,
+    ) -> Self {
+        Self {
+            hands,
+            stock: Deck::from(stock),
+            ..Self::seated(StdRng::seed_from_u64(seed), other(leader))
+        }
+    }
+
+    /// A game with no card dealt yet and `dealer` to deal.
+    fn seated(rng: StdRng, dealer: PlayerId) -> Self {
+        Self {
+            rng,
+            stock: Deck::new(),
+            hands: Default::default(),
+            trick: [None; SEATS],
+            captured: Default::default(),
+            leader: other(dealer),
+            dealer,
+            score: (0, 0),
+            history: Vec::new(),
+            hand_points: [0; SEATS],
+            cappotto: None,
+            summary: Vec::new(),
+        }
+    }
+
+    /// Returns the player whose turn it is to move.
+    pub fn current_player(&self) -> PlayerId {
+        if self.trick[self.leader.as_usize()].is_some() {
+            other(self.leader)
+        } else {
+            self.leader
+        }
+    }
+
+    /// Returns the current scores: (player_0_score, player_1_score).
+    pub fn score(&self) -> (u8, u8) {
+        self.score
+    }
+
+    /// Returns the current status of the game.
+    pub fn status(&self) -> Status {
+        if TressetteRules::is_game_over(self.score) {
+            let winner = if self.score.0 > self.score.1 { 0 } else { 1 };
+            Status::Finished { winner }
+        } else {
+            Status::Ongoing
+        }
+    }
+
+    /// Returns the cards in the given player's hand.
+    pub fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        &self.hands[player.as_usize()]
+    }
+
+    /// Returns the cards played in the ongoing trick, indexed by player.
+    pub fn current_trick(&self) -> &[Option<TressetteCard>; SEATS] {
+        &self.trick
+    }
+
+    /// Returns the player who led the current trick.
+    pub fn trick_leader(&self) -> PlayerId {
+        self.leader
+    }
+
+    /// Returns the number of cards left in the stock.
+    pub fn stock_len(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// Returns all legal cards for the current player.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+
+        let hand = &self.hands[self.current_player().as_usize()];
+        match self.trick[self.leader.as_usize()] {
+            Some(led) if hand.iter().any(|c| c.suit() == led.suit()) => hand
+                .iter()
+                .filter(|c| c.suit() == led.suit())
+                .copied()
+                .collect(),
+            _ => hand.clone(),
+        }
+    }
+
+    /// Plays a card for the current player, drawing from the stock once the
+    /// trick is complete.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+
+        let player = self.current_player();
+        if !self.legal_cards().contains(&card) {
+            if !self.hand(player).contains(&card) {
+                return Err(Error::CardNotInHand { card, player });
+            }
+            let required_suit = self.trick[self.leader.as_usize()]
+                .map(|c| c.suit())
+                .ok_or(Error::InternalError("No leading suit".to_string()))?;
+            return Err(Error::MustFollowSuit {
+                suit: card.suit(),
+                required_suit,
+            });
+        }
+
+        self.hands[player.as_usize()].retain(|&c| c != card);
+        self.trick[player.as_usize()] = Some(card);
+
+        let effect = if self.trick.iter().all(Option::is_some) {
+            self.complete_trick()
+        } else {
+            MoveEffect::CardPlayed
+        };
+        self.history.push((card, effect));
+
+        Ok(effect)
+    }
+
+    /// Returns the card history.
+    pub fn history(&self) -> &[(TressetteCard, MoveEffect)] {
+        &self.history
+    }
+
+    fn complete_trick(&mut self) -> MoveEffect {
+        let led = self.trick[self.leader.as_usize()];
+        let follow = self.trick[other(self.leader).as_usize()];
+        let winner = match (led, follow) {
+            (Some(led), Some(follow)) if follow.suit() == led.suit() && follow > led => {
+                other(self.leader)
+            }
+            _ => self.leader,
+        };
+
+        self.captured[winner.as_usize()].extend(self.trick.iter().flatten());
+        self.trick = [None; SEATS];
+        self.leader = winner;
+
+        // The winner draws first, then the loser.
+        for player in [winner, other(winner)] {
+            if let Some(card) = self.stock.draw() {
+                self.hands[player.as_usize()].push(card);
+            }
+        }
+
+        if self.hands.iter().all(Vec::is_empty) {
+            self.complete_hand(winner)
+        } else {
+            MoveEffect::TrickCompleted { winner }
+        }
+    }
+
+    fn complete_hand(&mut self, last_trick_winner: PlayerId) -> MoveEffect {
+        let mut points = self
+            .captured
+            .each_ref()
+            .map(|cards| cards.iter().map(|c| thirds(*c)).sum::<u8>() / 3);
+        let mut last_trick = [0; SEATS];
+        last_trick[last_trick_winner.as_usize()] = 1;
+        for (points, bonus) in points.iter_mut().zip(last_trick) {
+            *points += bonus;
+        }
+        self.score.0 += points[0];
+        self.score.1 += points[1];
+        self.hand_points = points;
+        self.cappotto = took_every_trick(&self.captured);
+
+        self.summary = card_lines(&self.captured);
+        self.summary.extend([
+            SummaryLine::new("Last trick", &last_trick),
+            SummaryLine::new("Hand", &points),
+            SummaryLine::new("Total", &[self.score.0, self.score.1]),
+        ]);
+
+        if TressetteRules::is_game_over(self.score) {
+            MoveEffect::GameOver {
+                trick_winner: last_trick_winner,
+            }
+        } else {
+            self.dealer = other(self.dealer);
+            self.leader = other(self.dealer);
+            self.deal();
+            MoveEffect::HandComplete {
+                trick_winner: last_trick_winner,
+            }
+        }
+    }
+
+    /// Shuffles a full deck, deals both hands and leaves the rest as stock.
+    fn deal(&mut self) {
+        self.captured = Default::default();
+        self.stock = shuffled_deck(&mut self.rng);
+
+        let mut to_deal_to = other(self.dealer);
+        for _ in 0..HAND_SIZE / 5 * SEATS {
+            if let Some(cards) = self.stock.draw_n(5) {
+                self.hands[to_deal_to.as_usize()].extend(cards);
+            }
+            to_deal_to = other(to_deal_to);
+        }
+    }
+}
+
+impl CardGame for TressetteDue {
+    fn seats(&self) -> usize {
+        SEATS
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        TressetteDue::hand(self, player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        TressetteDue::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        TressetteDue::current_trick(self)
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        TressetteDue::trick_leader(self)
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        TressetteDue::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        TressetteDue::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.history().last().map(|(_, effect)| *effect)
+    }
+
+    fn score(&self) -> Vec<u8> {
+        let (player_0, player_1) = TressetteDue::score(self);
+        vec![player_0, player_1]
+    }
+
+    fn status(&self) -> Status {
+        TressetteDue::status(self)
+    }
+
+    fn stock_len(&self) -> usize {
+        TressetteDue::stock_len(self)
+    }
+
+    fn shows_drawn_cards(&self) -> bool {
+        true
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn cappotto(&self) -> Option<usize> {
+        self.cappotto
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+
+    fn dealer(&self) -> Option<PlayerId> {
+        Some(self.dealer)
+    }
+}
+
+impl Default for TressetteDue {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+/// Returns the opponent of `player`.
+fn other(player: PlayerId) -> PlayerId {
+    if player == PlayerId::PLAYER_0 {
+        PlayerId::PLAYER_1
+    } else {
+        PlayerId::PLAYER_0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shuftlib::core::{Suit, italian::ItalianRank};
+
+    use super::*;
+
+    /// A game with `hands` dealt, `stock` left to draw from its end and
+    /// player 0 leading.
+    fn game_with(hands: [Vec<TressetteCard>; SEATS], stock: Vec<TressetteCard>) -> TressetteDue {
+        TressetteDue::from_deal(hands, stock, PlayerId::PLAYER_0, 0)
+    }
+
+    #[test]
+    fn led_suit_must_be_followed() {
+        let mut game = game_with(
+            [
+                vec![TressetteCard::new(ItalianRank::Four, Suit::Hearts)],
+                vec![
+                    TressetteCard::new(ItalianRank::Ace, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Three, Suit::Clubs),
+                ],
+            ],
+            Vec::new(),
+        );
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Hearts))
+            .unwrap();
+        assert_eq!(
+            game.legal_cards(),
+            vec![TressetteCard::new(ItalianRank::Ace, Suit::Hearts)]
+        );
+        assert_eq!(
+            game.play_card(TressetteCard::new(ItalianRank::Three, Suit::Clubs)),
+            Err(Error::MustFollowSuit {
+                suit: Suit::Clubs,
+                required_suit: Suit::Hearts,
+            })
+        );
+    }
+
+    #[test]
+    fn trick_goes_to_the_highest_card_of_the_led_suit() {
+        let mut game = game_with(
+            [
+                vec![
+                    TressetteCard::new(ItalianRank::Ace, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Four, Suit::Spades),
+                ],
+                vec![
+                    TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Three, Suit::Clubs),
+                ],
+            ],
+            Vec::new(),
+        );
+        game.play_card(TressetteCard::new(ItalianRank::Ace, Suit::Hearts))
+            .unwrap();
+        assert_eq!(
+            game.play_card(TressetteCard::new(ItalianRank::Three, Suit::Hearts)),
+            Ok(MoveEffect::TrickCompleted {
+                winner: PlayerId::PLAYER_1
+            })
+        );
+        assert_eq!(game.current_player(), PlayerId::PLAYER_1);
+
+        // A higher card of another suit doesn't take the trick.
+        let mut game = game_with(
+            [
+                vec![
+                    TressetteCard::new(ItalianRank::Ace, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Four, Suit::Spades),
+                ],
+                vec![
+                    TressetteCard::new(ItalianRank::Three, Suit::Clubs),
+                    TressetteCard::new(ItalianRank::Two, Suit::Clubs),
+                ],
+            ],
+            Vec::new(),
+        );
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Spades))
+            .unwrap();
+        assert_eq!(
+            game.play_card(TressetteCard::new(ItalianRank::Three, Suit::Clubs)),
+            Ok(MoveEffect::TrickCompleted {
+                winner: PlayerId::PLAYER_0
+            })
+        );
+    }
+
+    #[test]
+    fn winner_draws_before_the_loser() {
+        let mut game = game_with(
+            [
+                vec![
+                    TressetteCard::new(ItalianRank::Four, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Five, Suit::Spades),
+                ],
+                vec![
+                    TressetteCard::new(ItalianRank::Three, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Six, Suit::Spades),
+                ],
+            ],
+            vec![
+                TressetteCard::new(ItalianRank::King, Suit::Clubs),
+                TressetteCard::new(ItalianRank::Ace, Suit::Diamonds),
+            ],
+        );
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Hearts))
+            .unwrap();
+        game.play_card(TressetteCard::new(ItalianRank::Three, Suit::Hearts))
+            .unwrap();
+
+        // The stock is drawn from its end, so the winner gets the ace.
+        assert!(
+            game.hand(PlayerId::PLAYER_1)
+                .contains(&TressetteCard::new(ItalianRank::Ace, Suit::Diamonds))
+        );
+        assert!(
+            game.hand(PlayerId::PLAYER_0)
+                .contains(&TressetteCard::new(ItalianRank::King, Suit::Clubs))
+        );
+        assert_eq!(game.stock_len(), 0);
+    }
+
+    #[test]
+    fn hand_is_played_out_once_the_stock_is_empty() {
+        let mut game = game_with(
+            [
+                vec![
+                    TressetteCard::new(ItalianRank::Ace, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Four, Suit::Spades),
+                ],
+                vec![
+                    TressetteCard::new(ItalianRank::Four, Suit::Hearts),
+                    TressetteCard::new(ItalianRank::Five, Suit::Spades),
+                ],
+            ],
+            Vec::new(),
+        );
+        game.play_card(TressetteCard::new(ItalianRank::Ace, Suit::Hearts))
+            .unwrap();
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Hearts))
+            .unwrap();
+        // Nothing left to draw: the hands just get shorter.
+        assert_eq!(game.hand(PlayerId::PLAYER_0).len(), 1);
+        assert_eq!(game.hand(PlayerId::PLAYER_1).len(), 1);
+
+        game.play_card(TressetteCard::new(ItalianRank::Four, Suit::Spades))
+            .unwrap();
+        assert_eq!(
+            game.play_card(TressetteCard::new(ItalianRank::Five, Suit::Spades)),
+            Ok(MoveEffect::HandComplete {
+                trick_winner: PlayerId::PLAYER_1
+            })
+        );
+        // The ace makes a point, the last trick another one.
+        assert_eq!(game.score(), (1, 1));
+        // And the next hand is dealt from a full deck.
+        assert_eq!(game.hand(PlayerId::PLAYER_0).len(), HAND_SIZE);
+        assert_eq!(game.stock_len(), 20);
+    }
+}
@@ -0,0 +1,427 @@
+//! Four-player Tressette, played to the house rules of a [`RulesConfig`].
+//!
+//! Cards are dealt and tricks played by the Tressette engine of shuftlib,
+//! while the score is kept here: the cards each team takes, the last trick
+//! and the accusi declared are counted at the end of each hand, and the game
+//! ends when a team reaches the target.
+//!
+//! The same game is played con il morto: three players, the fourth hand
+//! lying face up and played by the player across from it.
+
+use rand::{SeedableRng, rngs::StdRng};
+use shuftlib::{
+    tressette::{Error, Game, Status, TressetteCard},
+    trick_taking::{PLAYERS, PlayerId},
+};
+
+use super::{
+    CardGame, MoveEffect, PendingPhase, RulesConfig, SummaryLine, Thirds,
+    accusi::{Accuso, accusi},
+    card_lines,
+    deal::Deal,
+    deal_tressette,
+    record::{InvalidMove, Move},
+    seat_before, thirds, took_every_trick, tressette_from_deal,
+};
+
+/// Number of cards dealt to each player at the start of a hand.
+const HAND_SIZE: usize = 10;
+
+/// Seat of the dummy hand when playing con il morto, across from the player
+/// controlling it.
+pub const DUMMY: PlayerId = PlayerId::PLAYER_2;
+
+/// An error that can occur while declaring accusi.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccusoError {
+    /// Accusi are not played with the chosen rules.
+    NotAllowed,
+    /// The player already played a card this hand.
+    TooLate,
+    /// The player doesn't hold the cards to declare it.
+    NotInHand(Accuso),
+    /// The player already declared it this hand.
+    AlreadyDeclared(Accuso),
+}
+
+#[derive(Debug)]
+pub struct TressetteQuattro {
+    rng: StdRng,
+    game: Game,
+    /// Player who dealt the hand being played.
+    dealer: PlayerId,
+    rules: RulesConfig,
+    dummy: Option<PlayerId>,
+    /// Accusi declared in the ongoing hand.
+    declared: Vec<(PlayerId, Accuso)>,
+    /// Cards taken in the ongoing hand, by team.
+    captured: [Vec<TressetteCard>; 2],
+    /// Thirds left over from the previous hands, when they're carried.
+    carried: [u8; 2],
+    score: [u8; 2],
+    last_effect: Option<MoveEffect>,
+    /// Points each team made in the last hand completed.
+    hand_points: [u8; 2],
+    /// Team that took every trick of the last hand completed, if any.
+    cappotto: Option<usize>,
+    /// What each team made in the last hand completed.
+    summary: Vec<SummaryLine>,
+}
+
+impl TressetteQuattro {
+    /// Creates a new game with the first hand already dealt. Games created
+    /// from the same `seed` deal the same cards.
+    pub fn new(rules: RulesConfig, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::dealt(deal_tressette(&mut rng), rng, rules)
+    }
+
+    /// Creates a new game with the first hand dealt as in `deal`. The hands
+    /// after it are shuffled from `seed`.
+    pub fn from_deal(rules: RulesConfig, deal: &Deal, seed: u64) -> Self {
+        Self::dealt(
+            tressette_from_deal(deal),
+            StdRng::seed_from_u64(seed),
+            rules,
+        )
+    }
+
+    fn dealt(game: Game, rng: StdRng, rules: RulesConfig) -> Self {
+        Self {
+            dealer: seat_before(game.trick_leader()),
+            game,
+            rng,
+            rules,
+            dummy: None,
+            declared: Vec::new(),
+            captured: Default::default(),
+            carried: [0; 2],
+            score: [0; 2],
+            last_effect: None,
+            hand_points: [0; 2],
+            cappotto: None,
+            summary: Vec::new(),
+        }
+    }
+
+    /// Creates a new game con il morto, the [`DUMMY`] seat being played face
+    /// up by the player across from it.
+    pub fn morto(rules: RulesConfig, seed: u64) -> Self {
+        Self {
+            dummy: Some(DUMMY),
+            ..Self::new(rules, seed)
+        }
+    }
+
+    /// Creates a new game con il morto with the first hand dealt as in
+    /// `deal`.
+    pub fn morto_from_deal(rules: RulesConfig, deal: &Deal, seed: u64) -> Self {
+        Self {
+            dummy: Some(DUMMY),
+            ..Self::from_deal(rules, deal, seed)
+        }
+    }
+
+    /// Returns the player whose turn it is to move.
+    pub fn current_player(&self) -> PlayerId {
+        self.game.current_player()
+    }
+
+    /// Accusi declared in the ongoing hand, in the order they were declared.
+    pub fn declared(&self) -> &[(PlayerId, Accuso)] {
+        &self.declared
+    }
+
+    /// Points declared in the ongoing hand, by team.
+    pub fn declared_points(&self) -> [u8; 2] {
+        let mut points = [0; 2];
+        for (player, accuso) in &self.declared {
+            points[player.as_usize() % 2] += accuso.points();
+        }
+        points
+    }
+
+    /// Accusi the current player can still declare: none once they played
+    /// their first card of the hand, or if accusi aren't played.
+    pub fn accusi(&self) -> Vec<Accuso> {
+        let player = self.game.current_player();
+        let hand = self.game.hand(player);
+        if !self.rules.accusi || hand.len() < HAND_SIZE {
+            return vec![];
+        }
+        accusi(hand)
+            .into_iter()
+            .filter(|accuso| !self.declared.contains(&(player, *accuso)))
+            .collect()
+    }
+
+    /// Declares an accuso for the current player.
+    pub fn declare(&mut self, accuso: Accuso) -> Result<(), AccusoError> {
+        let player = self.game.current_player();
+        if !self.rules.accusi {
+            return Err(AccusoError::NotAllowed);
+        }
+        if self.game.hand(player).len() < HAND_SIZE {
+            return Err(AccusoError::TooLate);
+        }
+        if self.declared.contains(&(player, accuso)) {
+            return Err(AccusoError::AlreadyDeclared(accuso));
+        }
+        if !accusi(self.game.hand(player)).contains(&accuso) {
+            return Err(AccusoError::NotInHand(accuso));
+        }
+        self.declared.push((player, accuso));
+        Ok(())
+    }
+
+    /// Returns the score of each team.
+    pub fn score(&self) -> [u8; 2] {
+        self.score
+    }
+
+    /// Returns the current status of the game.
+    pub fn status(&self) -> Status {
+        let [first, second] = self.score;
+        if first.max(second) >= self.rules.target && first != second {
+            Status::Finished {
+                winner: u8::from(second > first),
+            }
+        } else {
+            Status::Ongoing
+        }
+    }
+
+    /// Returns all legal cards for the current player.
+    pub fn legal_cards(&self) -> Vec<TressetteCard> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return vec![];
+        }
+        self.game.legal_cards()
+    }
+
+    /// Plays a card for the current player.
+    pub fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        if matches!(self.status(), Status::Finished { .. }) {
+            return Err(Error::GameOver);
+        }
+
+        let mut trick: Vec<TressetteCard> = self
+            .game
+            .current_trick()
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        trick.push(card);
+        let mut effect = MoveEffect::from(self.game.play_card(card)?);
+        match effect {
+            MoveEffect::CardPlayed => {}
+            MoveEffect::TrickCompleted { winner } => self.take(winner, &trick),
+            MoveEffect::HandComplete { trick_winner } | MoveEffect::GameOver { trick_winner } => {
+                self.take(trick_winner, &trick);
+                effect = self.complete_hand(trick_winner);
+            }
+        }
+        self.last_effect = Some(effect);
+
+        Ok(effect)
+    }
+
+    fn take(&mut self, winner: PlayerId, trick: &[TressetteCard]) {
+        self.captured[winner.as_usize() % 2].extend(trick);
+    }
+
+    fn complete_hand(&mut self, last_trick_winner: PlayerId) -> MoveEffect {
+        let mut last_trick = [0; 2];
+        if self.rules.last_trick_bonus {
+            last_trick[last_trick_winner.as_usize() % 2] = 1;
+        }
+        let accusi = self.declared_points();
+        let carried = self.carried;
+        let mut hand = [0; 2];
+        for (team, declared) in accusi.into_iter().enumerate() {
+            let taken: u8 = self.captured[team].iter().map(|c| thirds(*c)).sum();
+            let thirds = taken + last_trick[team] * 3 + carried[team];
+            hand[team] = thirds / 3 + declared;
+            self.score[team] += hand[team];
+            self.carried[team] = match self.rules.thirds {
+                Thirds::Dropped => 0,
+                Thirds::Carried => thirds % 3,
+            };
+        }
+        self.hand_points = hand;
+        self.cappotto = took_every_trick(&self.captured);
+
+        self.summary = card_lines(&self.captured);
+        if self.rules.last_trick_bonus {
+            self.summary
+                .push(SummaryLine::new("Last trick", &last_trick));
+        }
+        if self.rules.accusi {
+            self.summary.push(SummaryLine::new("Accusi", &accusi));
+        }
+        if self.rules.thirds == Thirds::Carried {
+            self.summary
+                .push(SummaryLine::new("Thirds carried over", &carried));
+        }
+        self.summary.push(SummaryLine::new("Hand", &hand));
+        self.summary.push(SummaryLine::new("Total", &self.score));
+
+        self.captured = Default::default();
+        self.declared.clear();
+
+        if matches!(self.status(), Status::Finished { .. }) {
+            return MoveEffect::GameOver {
+                trick_winner: last_trick_winner,
+            };
+        }
+        // shuftlib stops dealing once its own target is reached: start over
+        // with a fresh deal, the score being kept here.
+        if matches!(self.game.status(), Status::Finished { .. }) {
+            self.game = deal_tressette(&mut self.rng);
+        }
+        self.dealer = seat_before(self.game.trick_leader());
+        MoveEffect::HandComplete {
+            trick_winner: last_trick_winner,
+        }
+    }
+}
+
+impl Default for TressetteQuattro {
+    fn default() -> Self {
+        Self::new(RulesConfig::default(), rand::random())
+    }
+}
+
+impl CardGame for TressetteQuattro {
+    fn seats(&self) -> usize {
+        PLAYERS
+    }
+
+    fn hand(&self, player: PlayerId) -> &[TressetteCard] {
+        self.game.hand(player)
+    }
+
+    fn current_player(&self) -> PlayerId {
+        TressetteQuattro::current_player(self)
+    }
+
+    fn current_trick(&self) -> &[Option<TressetteCard>] {
+        self.game.current_trick()
+    }
+
+    fn trick_leader(&self) -> PlayerId {
+        self.game.trick_leader()
+    }
+
+    fn legal_cards(&self) -> Vec<TressetteCard> {
+        TressetteQuattro::legal_cards(self)
+    }
+
+    fn play_card(&mut self, card: TressetteCard) -> Result<MoveEffect, Error> {
+        TressetteQuattro::play_card(self, card)
+    }
+
+    fn last_effect(&self) -> Option<MoveEffect> {
+        self.last_effect
+    }
+
+    fn score(&self) -> Vec<u8> {
+        TressetteQuattro::score(self).to_vec()
+    }
+
+    fn status(&self) -> Status {
+        TressetteQuattro::status(self)
+    }
+
+    fn pending_phase(&self) -> Option<PendingPhase> {
+        (!self.accusi().is_empty()).then_some(PendingPhase::Accusi)
+    }
+
+    fn pending_moves(&self) -> Vec<Move> {
+        self.accusi().into_iter().map(Move::Declare).collect()
+    }
+
+    /// Bots declare every accuso they hold.
+    fn bot_move(&self) -> Option<Move> {
+        self.accusi().first().copied().map(Move::Declare)
+    }
+
+    fn apply(&mut self, mv: &Move) -> Result<(), InvalidMove> {
+        let made = match mv {
+            Move::Play(card) => self.play_card(*card).is_ok(),
+            Move::Declare(accuso) => self.declare(*accuso).is_ok(),
+            _ => false,
+        };
+        made.then_some(()).ok_or_else(|| InvalidMove(mv.clone()))
+    }
+
+    fn declared(&self) -> &[(PlayerId, Accuso)] {
+        TressetteQuattro::declared(self)
+    }
+
+    fn dummy(&self) -> Option<PlayerId> {
+        self.dummy
+    }
+
+    fn hand_points(&self) -> &[u8] {
+        &self.hand_points
+    }
+
+    fn cappotto(&self) -> Option<usize> {
+        self.cappotto
+    }
+
+    fn hand_summary(&self) -> &[SummaryLine] {
+        &self.summary
+    }
+
+    fn dealer(&self) -> Option<PlayerId> {
+        Some(self.dealer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shuftlib::core::{Suit, italian::ItalianRank};
+
+    use super::*;
+    use crate::games::fixtures::suit_per_player;
+
+    /// A game where each player is dealt a whole suit, the first player
+    /// holding Hearts and leading.
+    fn suits_dealt() -> TressetteQuattro {
+        let deal = Deal::new(suit_per_player(), PlayerId::PLAYER_0).unwrap();
+        TressetteQuattro::from_deal(RulesConfig::default(), &deal, 0)
+    }
+
+    #[test]
+    fn declare_accuso_held() {
+        let mut game = suits_dealt();
+        assert_eq!(game.accusi(), [Accuso::Napoletana(Suit::Hearts)]);
+        assert_eq!(game.declare(Accuso::Napoletana(Suit::Hearts)), Ok(()));
+        assert_eq!(
+            game.declared(),
+            [(PlayerId::PLAYER_0, Accuso::Napoletana(Suit::Hearts))]
+        );
+    }
+
+    #[test]
+    fn declare_rejects_accuso_not_held() {
+        let mut game = suits_dealt();
+        let buongioco = Accuso::Buongioco {
+            rank: ItalianRank::Ace,
+            count: 4,
+        };
+        assert_eq!(
+            game.declare(buongioco),
+            Err(AccusoError::NotInHand(buongioco))
+        );
+        let napoletana = Accuso::Napoletana(Suit::Spades);
+        assert_eq!(
+            game.declare(napoletana),
+            Err(AccusoError::NotInHand(napoletana))
+        );
+        assert!(game.declared().is_empty());
+    }
+}
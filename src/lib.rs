@@ -1,2 +1,6 @@
 pub mod camera;
 pub mod game_logic;
+pub mod games;
+#[cfg(feature = "net-conditioner")]
+pub mod net;
+pub mod storage;
@@ -0,0 +1,184 @@
+//! Links carrying messages between the client and a local server, and a
+//! wrapper making them behave like a bad network.
+//!
+//! This is for development only: the game plays locally and sends nothing.
+//! Tests use it to see how the table copes with moves that arrive late, out
+//! of order or not at all, e.g. a bot's card landing while another is still
+//! moving to the table or the trick is being collected. It's only built with
+//! the `net-conditioner` feature, as in `cargo test --features net-conditioner`.
+//!
+//! Time is whatever the caller says it is, as the time elapsed since the
+//! link was opened: `Time::elapsed()` in a running app, or a made up clock in
+//! a test that steps through it.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    time::Duration,
+};
+
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+/// The other end of the link is gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disconnected;
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "disconnected")
+    }
+}
+
+/// One end of a link messages of type `M` are sent over.
+pub trait Transport<M> {
+    /// Sends `message` to the other end.
+    fn send(&mut self, now: Duration, message: M) -> Result<(), Disconnected>;
+
+    /// Returns the messages arrived by `now`, in the order they arrived.
+    fn receive(&mut self, now: Duration) -> Result<Vec<M>, Disconnected>;
+}
+
+/// One end of a link within the same process, every message arriving as
+/// soon as it's sent. Dropping one end disconnects the other.
+pub struct LocalLink<M> {
+    outgoing: Sender<M>,
+    incoming: Receiver<M>,
+}
+
+/// Opens a link within the same process, returning the ends of the client
+/// and of the server.
+pub fn local_link<M>() -> (LocalLink<M>, LocalLink<M>) {
+    let (to_server, from_client) = mpsc::channel();
+    let (to_client, from_server) = mpsc::channel();
+    let client = LocalLink {
+        outgoing: to_server,
+        incoming: from_server,
+    };
+    let server = LocalLink {
+        outgoing: to_client,
+        incoming: from_client,
+    };
+    (client, server)
+}
+
+impl<M> Transport<M> for LocalLink<M> {
+    fn send(&mut self, _now: Duration, message: M) -> Result<(), Disconnected> {
+        self.outgoing.send(message).map_err(|_| Disconnected)
+    }
+
+    fn receive(&mut self, _now: Duration) -> Result<Vec<M>, Disconnected> {
+        let mut messages = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => return Ok(messages),
+                // What was sent before the other end left still arrives.
+                Err(TryRecvError::Disconnected) if !messages.is_empty() => return Ok(messages),
+                Err(TryRecvError::Disconnected) => return Err(Disconnected),
+            }
+        }
+    }
+}
+
+/// How bad the network is. The default is a perfect one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NetworkConditions {
+    /// Time every message takes to arrive.
+    pub latency: Duration,
+    /// Most time a message can take on top of the latency, picked at random
+    /// for each message. It doesn't change the order messages arrive in.
+    pub jitter: Duration,
+    /// Chance, from 0 to 1, of a message overtaking the ones sent before it
+    /// and still on their way.
+    pub reorder: f64,
+    /// Time the link drops at, if it does. Messages still on their way are
+    /// lost.
+    pub disconnect_at: Option<Duration>,
+}
+
+/// A [`Transport`] receiving messages as if over a network in the given
+/// [`NetworkConditions`]. Wrap both ends of a link to slow down both ways.
+pub struct Conditioned<T, M> {
+    inner: T,
+    conditions: NetworkConditions,
+    rng: StdRng,
+    /// Messages received from the inner transport but not delivered yet,
+    /// with when they're delivered, the first to be delivered first.
+    in_flight: VecDeque<(Duration, M)>,
+}
+
+impl<T: Transport<M>, M> Conditioned<T, M> {
+    /// Wraps `inner`, the jitter and the reordering picked from `seed`, to
+    /// behave the same every time.
+    pub fn new(inner: T, conditions: NetworkConditions, seed: u64) -> Self {
+        Self {
+            inner,
+            conditions,
+            rng: StdRng::seed_from_u64(seed),
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    /// Number of messages on their way.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    fn disconnected(&self, now: Duration) -> bool {
+        self.conditions.disconnect_at.is_some_and(|at| now >= at)
+    }
+
+    /// Picks when `message`, arrived at `now`, is delivered and puts it on
+    /// its way.
+    fn dispatch(&mut self, now: Duration, message: M) {
+        let jitter = self.conditions.jitter.as_nanos() as u64;
+        let due =
+            now + self.conditions.latency + Duration::from_nanos(self.rng.random_range(0..=jitter));
+        let overtakes = !self.in_flight.is_empty()
+            && self
+                .rng
+                .random_bool(self.conditions.reorder.clamp(0.0, 1.0));
+        if overtakes {
+            let first = self.in_flight.front().map_or(due, |(at, _)| *at);
+            self.in_flight.push_front((due.min(first), message));
+        } else {
+            let last = self.in_flight.back().map_or(due, |(at, _)| *at);
+            self.in_flight.push_back((due.max(last), message));
+        }
+    }
+}
+
+impl<T: Transport<M>, M> Transport<M> for Conditioned<T, M> {
+    fn send(&mut self, now: Duration, message: M) -> Result<(), Disconnected> {
+        if self.disconnected(now) {
+            return Err(Disconnected);
+        }
+        self.inner.send(now, message)
+    }
+
+    fn receive(&mut self, now: Duration) -> Result<Vec<M>, Disconnected> {
+        if self.disconnected(now) {
+            self.in_flight.clear();
+            return Err(Disconnected);
+        }
+        // What's on its way still arrives after the other end left.
+        let inner_left = match self.inner.receive(now) {
+            Ok(arrived) => {
+                for message in arrived {
+                    self.dispatch(now, message);
+                }
+                false
+            }
+            Err(Disconnected) => true,
+        };
+        if inner_left && self.in_flight.is_empty() {
+            return Err(Disconnected);
+        }
+        let mut delivered = Vec::new();
+        while self.in_flight.front().is_some_and(|(due, _)| *due <= now) {
+            delivered.extend(self.in_flight.pop_front().map(|(_, message)| message));
+        }
+        Ok(delivered)
+    }
+}
@@ -0,0 +1,126 @@
+//! Data kept between sessions: files in the user's data directory on
+//! native, the browser's local storage on the web.
+//!
+//! Data is stored as RON, except for plain text meant to be read and shared
+//! by players, like game records. Files written by players can be JSON too.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::{fs, io, path::PathBuf};
+
+    /// Directory the files are kept in, unless the user's data directory
+    /// can't be told.
+    fn dir() -> io::Result<PathBuf> {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .map(|dir| dir.join("shuftle"))
+            .ok_or_else(|| io::Error::other("no data directory to keep files in"))
+    }
+
+    fn path(key: &str, extension: &str) -> io::Result<PathBuf> {
+        Ok(dir()?.join(format!("{key}.{extension}")))
+    }
+
+    pub fn location(key: &str, extension: &str) -> io::Result<String> {
+        Ok(path(key, extension)?.display().to_string())
+    }
+
+    pub fn read(key: &str, extension: &str) -> Option<String> {
+        fs::read_to_string(path(key, extension).ok()?).ok()
+    }
+
+    pub fn write(key: &str, extension: &str, contents: &str) -> io::Result<()> {
+        fs::create_dir_all(dir()?)?;
+        fs::write(path(key, extension)?, contents)
+    }
+
+    pub fn remove(key: &str, extension: &str) -> io::Result<()> {
+        match fs::remove_file(path(key, extension)?) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::io;
+
+    fn storage() -> io::Result<web_sys::Storage> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| io::Error::other("local storage is not available"))
+    }
+
+    fn item(key: &str, extension: &str) -> String {
+        format!("shuftle.{key}.{extension}")
+    }
+
+    pub fn location(key: &str, extension: &str) -> io::Result<String> {
+        Ok(format!("the local storage item {}", item(key, extension)))
+    }
+
+    pub fn read(key: &str, extension: &str) -> Option<String> {
+        storage()
+            .ok()?
+            .get_item(&item(key, extension))
+            .ok()
+            .flatten()
+    }
+
+    pub fn write(key: &str, extension: &str, contents: &str) -> io::Result<()> {
+        storage()?
+            .set_item(&item(key, extension), contents)
+            .map_err(|_| io::Error::other("local storage is full"))
+    }
+
+    pub fn remove(key: &str, extension: &str) -> io::Result<()> {
+        storage()?
+            .remove_item(&item(key, extension))
+            .map_err(|_| io::Error::other("local storage can't be written"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+use native as backend;
+#[cfg(target_arch = "wasm32")]
+use web as backend;
+
+/// Returns what was last stored under `key`, if anything.
+pub fn read(key: &str) -> Option<String> {
+    backend::read(key, "ron")
+}
+
+/// Stores `contents` under `key`, replacing what was there.
+pub fn write(key: &str, contents: &str) -> std::io::Result<()> {
+    backend::write(key, "ron", contents)
+}
+
+/// Forgets what was stored under `key`.
+pub fn remove(key: &str) -> std::io::Result<()> {
+    backend::remove(key, "ron")
+}
+
+/// Returns the JSON stored under `key`, if any.
+pub fn read_json(key: &str) -> Option<String> {
+    backend::read(key, "json")
+}
+
+/// Returns the plain text last stored under `key`, if any.
+pub fn read_text(key: &str) -> Option<String> {
+    backend::read(key, "txt")
+}
+
+/// Stores the plain text `contents` under `key`, replacing what was there.
+pub fn write_text(key: &str, contents: &str) -> std::io::Result<()> {
+    backend::write(key, "txt", contents)
+}
+
+/// Where the plain text stored under `key` can be found by the player.
+pub fn text_location(key: &str) -> std::io::Result<String> {
+    backend::location(key, "txt")
+}
@@ -0,0 +1,138 @@
+//! Moves sent by a local server reaching the client over a bad network.
+
+use std::time::Duration;
+
+use shuftle_client_core::net::{
+    Conditioned, Disconnected, LocalLink, NetworkConditions, Transport, local_link,
+};
+
+/// A move as the server sends it.
+type Move = &'static str;
+
+/// Time between two frames of the client.
+const FRAME: Duration = Duration::from_millis(16);
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn moves(count: usize) -> Vec<Move> {
+    ["play 1C", "play 3C", "play 7D", "play 10S", "play 2B"][..count].to_vec()
+}
+
+/// Opens a link whose client end receives in `conditions`, and has the
+/// server send `sent` at the start.
+fn open(
+    conditions: NetworkConditions,
+    sent: &[Move],
+) -> (Conditioned<LocalLink<Move>, Move>, LocalLink<Move>) {
+    let (client, mut server) = local_link();
+    for mv in sent {
+        server.send(Duration::ZERO, *mv).unwrap();
+    }
+    (Conditioned::new(client, conditions, 42), server)
+}
+
+/// Steps the client a frame at a time until `until`, returning the moves
+/// received and when.
+fn run(client: &mut Conditioned<LocalLink<Move>, Move>, until: Duration) -> Vec<(Duration, Move)> {
+    let mut received = Vec::new();
+    let mut now = Duration::ZERO;
+    while now <= until {
+        for mv in client.receive(now).unwrap() {
+            received.push((now, mv));
+        }
+        now += FRAME;
+    }
+    received
+}
+
+#[test]
+fn perfect_network_delivers_at_once() {
+    let sent = moves(3);
+    let (mut client, _server) = open(NetworkConditions::default(), &sent);
+    assert_eq!(client.receive(Duration::ZERO), Ok(sent));
+}
+
+#[test]
+fn latency_delays_every_move() {
+    let sent = moves(3);
+    let conditions = NetworkConditions {
+        latency: ms(300),
+        ..Default::default()
+    };
+    let (mut client, _server) = open(conditions, &sent);
+    let received = run(&mut client, ms(1000));
+    assert!(
+        received
+            .iter()
+            .all(|(at, _)| *at >= ms(300) && *at < ms(300) + FRAME)
+    );
+    let received: Vec<Move> = received.into_iter().map(|(_, mv)| mv).collect();
+    assert_eq!(received, sent);
+}
+
+#[test]
+fn jitter_keeps_the_order() {
+    let sent = moves(5);
+    let conditions = NetworkConditions {
+        latency: ms(100),
+        jitter: ms(400),
+        ..Default::default()
+    };
+    let (mut client, _server) = open(conditions, &sent);
+    let received = run(&mut client, ms(1000));
+    assert!(
+        received
+            .iter()
+            .all(|(at, _)| *at >= ms(100) && *at < ms(500) + FRAME)
+    );
+    let received: Vec<Move> = received.into_iter().map(|(_, mv)| mv).collect();
+    assert_eq!(received, sent);
+}
+
+#[test]
+fn reordering_lets_moves_overtake() {
+    let sent = moves(2);
+    let conditions = NetworkConditions {
+        latency: ms(100),
+        reorder: 1.0,
+        ..Default::default()
+    };
+    let (mut client, _server) = open(conditions, &sent);
+    let received: Vec<Move> = run(&mut client, ms(200))
+        .into_iter()
+        .map(|(_, mv)| mv)
+        .collect();
+    assert_eq!(received, [sent[1], sent[0]]);
+}
+
+#[test]
+fn disconnect_loses_moves_on_their_way() {
+    let sent = moves(2);
+    let conditions = NetworkConditions {
+        latency: ms(300),
+        disconnect_at: Some(ms(200)),
+        ..Default::default()
+    };
+    let (mut client, _server) = open(conditions, &sent);
+    assert_eq!(client.receive(Duration::ZERO), Ok(Vec::new()));
+    assert_eq!(client.in_flight(), 2);
+    assert_eq!(client.receive(ms(200)), Err(Disconnected));
+    assert_eq!(client.in_flight(), 0);
+    assert_eq!(client.send(ms(250), sent[0]), Err(Disconnected));
+}
+
+#[test]
+fn moves_sent_before_the_server_leaves_still_arrive() {
+    let sent = moves(2);
+    let conditions = NetworkConditions {
+        latency: ms(300),
+        ..Default::default()
+    };
+    let (mut client, server) = open(conditions, &sent);
+    assert_eq!(client.receive(Duration::ZERO), Ok(Vec::new()));
+    drop(server);
+    assert_eq!(client.receive(ms(300)), Ok(sent));
+    assert_eq!(client.receive(ms(316)), Err(Disconnected));
+}